@@ -4,9 +4,9 @@ use unrar::{Archive, ListSplit};
 fn main() {
     // Basic args parsing
     // Usage: cargo run --example lister path/to/archive.rar
-    let args = std::env::args();
+    let mut args = std::env::args();
     let mut stderr = std::io::stderr();
-    let file = args.skip(1).next().unwrap_or_else(|| {
+    let file = args.nth(1).unwrap_or_else(|| {
         writeln!(&mut stderr, "Please pass an archive as argument!").unwrap();
         std::process::exit(1)
     });
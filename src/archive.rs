@@ -1,9 +1,15 @@
+use crate::dll;
 use crate::error::*;
-use crate::open_archive::{CursorBeforeHeader, List, ListSplit, OpenArchive, OpenMode, Process};
+use crate::open_archive::{
+    restore_dir_times, Callbacks, CursorBeforeFile, CursorBeforeHeader, ExtractOutcome, FileHeader,
+    List, ListSplit, OpenArchive, OpenMode, OverwritePolicy, Process, VolumeNaming,
+};
 use regex::Regex;
 use std::borrow::Cow;
-use std::iter::repeat;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::sync::OnceLock;
 
 fn multipart_extension() -> &'static Regex {
@@ -20,41 +26,140 @@ fn extension() -> &'static Regex {
 ///
 /// This struct provides two major classes of methods:
 ///    1. methods that do not touch the FS. These are opinionated utility methods
-///         that are based on RAR path conventions out in the wild. Most commonly, multipart
-///         files usually have extensions such as `.part08.rar` or `.r08.rar`. Since extracting
-///         must start at the first part, it may be helpful to figure that out using, for instance,
-///         [`archive.as_first_part()`](Archive::as_first_part)
+///       that are based on RAR path conventions out in the wild. Most commonly, multipart
+///       files usually have extensions such as `.part08.rar` or `.r08.rar`. Since extracting
+///       must start at the first part, it may be helpful to figure that out using, for instance,
+///       [`archive.as_first_part()`](Archive::as_first_part)
 ///    2. methods that open the underlying path in the specified mode
-///         (possible modes are [`List`], [`ListSplit`] and [`Process`]).
-///         These methods have the word `open` in them, are fallible operations,
-///         return [`OpenArchive`](struct.OpenArchive.html) inside a `Result` and are as follows:
-///         - [`open_for_listing`](Archive::open_for_listing) and
-///             [`open_for_listing_split`](Archive::open_for_listing_split): list the archive
-///             entries (skipping over content/payload)
-///         - [`open_for_processing`](Archive::open_for_processing): process archive entries
-///             as well as content/payload
-///         - [`break_open`](Archive::break_open): read archive even if an error is returned,
-///             if possible. The [`OpenMode`](open_archive/struct.OpenMode.html) must be provided
-///             explicitly.
+///       (possible modes are [`List`], [`ListSplit`] and [`Process`]).
+///       These methods have the word `open` in them, are fallible operations,
+///       return [`OpenArchive`](struct.OpenArchive.html) inside a `Result` and are as follows:
+///       - [`open_for_listing`](Archive::open_for_listing) and
+///         [`open_for_listing_split`](Archive::open_for_listing_split): list the archive
+///         entries (skipping over content/payload)
+///       - [`open_for_processing`](Archive::open_for_processing): process archive entries
+///         as well as content/payload
+///       - [`break_open`](Archive::break_open): read archive even if an error is returned,
+///         if possible. The [`OpenMode`](open_archive/struct.OpenMode.html) must be provided
+///         explicitly.
 pub struct Archive<'a> {
     filename: Cow<'a, Path>,
     password: Option<&'a [u8]>,
     comments: Option<&'a mut Vec<u8>>,
+    memory: Option<Vec<u8>>,
+    callbacks: Callbacks,
 }
 
 pub type Glob = PathBuf;
 
+/// The volumes of a multipart archive found on disk, see [`Archive::volume_paths`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VolumePaths {
+    /// the volumes found, in order, up to the first missing one
+    pub paths: Vec<PathBuf>,
+    /// whether the last volume found is the last volume of the archive
+    pub complete: bool,
+    /// the naming scheme the volumes were found with, `None` for a single-part archive
+    pub naming: Option<VolumeNaming>,
+}
+
+/// Totals over the entries of an archive along with its flags, see [`Archive::summary`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveSummary {
+    /// the number of entries, counting entries split across volumes once
+    pub entries: usize,
+    /// how many of the entries are directories
+    pub directories: usize,
+    /// the sum of the entries' unpacked sizes
+    pub unpacked_size: u64,
+    /// the sum of the entries' packed sizes, including all segments of split entries
+    pub packed_size: u64,
+    /// whether any entry is [encrypted](FileHeader::is_encrypted)
+    pub has_encrypted_entries: bool,
+    /// see [`OpenArchive::is_solid`]
+    pub is_solid: bool,
+    /// see [`OpenArchive::is_volume`]
+    pub is_volume: bool,
+    /// see [`OpenArchive::has_encrypted_headers`]
+    pub has_encrypted_headers: bool,
+    /// whether the unpacked size is implausibly large for the packed size, i.e. exceeds it
+    /// more than the [configured ratio](Archive::with_suspicious_ratio) allows.
+    ///
+    /// This is a cheap heuristic for decompression bombs from the sizes declared in the
+    /// headers, not a guarantee either way: the headers may not tell the truth, which only
+    /// unpacking finds out, see [`Archive::with_max_total_unpacked`].
+    pub suspicious_ratio: bool,
+}
+
+impl ArchiveSummary {
+    /// the packed size relative to the unpacked size, like
+    /// [`FileHeader::compression_ratio`] for the whole archive.
+    pub fn compression_ratio(&self) -> f64 {
+        match self.unpacked_size {
+            0 => 0.0,
+            unpacked_size => self.packed_size as f64 / unpacked_size as f64,
+        }
+    }
+}
+
+/// The outcome of [testing](Archive::test) the entries of an archive.
+#[derive(Debug)]
+pub struct TestReport {
+    /// the entries tested, in order, along with the result of testing each of them
+    pub entries: Vec<(FileHeader, UnrarResult<()>)>,
+}
+
+impl TestReport {
+    /// whether all entries passed the test
+    pub fn passed(&self) -> bool {
+        self.entries.iter().all(|(_, result)| result.is_ok())
+    }
+
+    /// the entries that failed the test, along with the reason
+    pub fn failures(&self) -> impl Iterator<Item = (&FileHeader, &UnrarError)> {
+        self.entries
+            .iter()
+            .filter_map(|(entry, result)| Some((entry, result.as_ref().err()?)))
+    }
+}
+
 impl<'a> Archive<'a> {
+    /// How many times larger than the packed size the unpacked size of all entries may be
+    /// by default before a [summary](Archive::summary) is
+    /// [suspicious](ArchiveSummary::suspicious_ratio), see [`Archive::with_suspicious_ratio`].
+    ///
+    /// Ordinary data rarely compresses beyond 1:20, while only long runs of the same
+    /// bytes get anywhere near 1:1000.
+    pub const DEFAULT_SUSPICIOUS_RATIO: u64 = 1000;
+
     /// Creates an `Archive` object to operate on a plain non-encrypted RAR archive.
     pub fn new<T>(file: &'a T) -> Self
     where
         T: AsRef<Path> + ?Sized,
     {
-        Archive {
-            filename: Cow::Borrowed(file.as_ref()),
-            password: None,
-            comments: None,
-        }
+        Archive::builder(file).build()
+    }
+
+    /// Creates an [`ArchiveBuilder`] to configure the password and callbacks
+    /// before opening the RAR archive at `file`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let archive = Archive::builder("data/crypted.rar")
+    ///     .password("unrar")
+    ///     .on_progress(|entry, bytes| println!("{}: {bytes} bytes", entry.filename.display()))
+    ///     .process()
+    ///     .unwrap();
+    /// let (data, _) = archive.read_header().unwrap().unwrap().read().unwrap();
+    /// assert_eq!(data, b"target\nCargo.lock\n");
+    /// ```
+    pub fn builder<T>(file: &'a T) -> ArchiveBuilder<'a>
+    where
+        T: AsRef<Path> + ?Sized,
+    {
+        ArchiveBuilder::new(Cow::Borrowed(file.as_ref()))
     }
 
     /// Creates an `Archive` object to operate on a password encrypted RAR archive.
@@ -63,12 +168,35 @@ impl<'a> Archive<'a> {
         F: AsRef<Path> + ?Sized,
         Pw: AsRef<[u8]> + ?Sized,
     {
-        Archive {
-            filename: Cow::Borrowed(file.as_ref()),
-            password: Some(password.as_ref()),
-            comments: None,
-        }
+        Archive::builder(file).password(password).build()
+    }
+    /// Creates an `Archive` object to operate on a password encrypted RAR archive, where the
+    /// password is only obtained once the UnRAR library asks for it, e.g. by prompting the user.
+    ///
+    /// `callback` is invoked each time a password is needed, both for archives with encrypted
+    /// headers and for encrypted entries. Its argument is the number of times it has been
+    /// invoked so far, including the current invocation, so implementations can give up after
+    /// a number of attempts. Returning `None` aborts the operation with
+    /// [`Code::MissingPassword`](crate::error::Code::MissingPassword).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let archive = Archive::with_password_callback("data/crypted.rar", |attempt| {
+    ///     (attempt == 1).then(|| b"unrar".to_vec())
+    /// });
+    /// let archive = archive.open_for_processing().unwrap().read_header().unwrap().unwrap();
+    /// assert_eq!(archive.read().unwrap().0, b"target\nCargo.lock\n");
+    /// ```
+    pub fn with_password_callback<F, C>(file: &'a F, callback: C) -> Self
+    where
+        F: AsRef<Path> + ?Sized,
+        C: FnMut(usize) -> Option<Vec<u8>> + Send + 'static,
+    {
+        Archive::builder(file).password_callback(callback).build()
     }
+
     /// Creates an `Archive` object to operate on a plain non-encrypted RAR archive.
     /// as opposed to [`new`](struct.Archive.html#method.new) that borrows from its input, this function takes ownership of it,
     /// potentially cloning if the input is a reference.
@@ -78,11 +206,78 @@ impl<'a> Archive<'a> {
     where
         T: Into<PathBuf>,
     {
-        Archive {
-            filename: Cow::Owned(file.into()),
-            password: None,
-            comments: None,
-        }
+        ArchiveBuilder::new(Cow::Owned(file.into())).build()
+    }
+
+    /// Creates an `Archive` object to operate on a RAR archive held in memory, e.g. received
+    /// over the network.
+    ///
+    /// The data never touches the disk: on Linux, it is handed to the UnRAR library through an
+    /// anonymous in-memory file. Opening such an archive fails with [`Code::EOpen`] on other
+    /// platforms, as well as for multipart archives since subsequent volumes can never be found.
+    ///
+    /// The filename of such an archive is empty, so the methods operating on it
+    /// (e.g. [`is_multipart`](Archive::is_multipart)) are of no use.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let data = std::fs::read("data/version.rar").unwrap();
+    /// # #[cfg(target_os = "linux")]
+    /// # {
+    /// let mut archive = Archive::from_bytes(data).open_for_listing().unwrap();
+    /// assert_eq!(archive.next().unwrap().unwrap().filename.as_os_str(), "VERSION");
+    /// # }
+    /// ```
+    ///
+    /// [`Code::EOpen`]: crate::error::Code::EOpen
+    pub fn from_bytes(data: Vec<u8>) -> Archive<'static> {
+        let mut archive = ArchiveBuilder::new(Cow::Owned(PathBuf::new())).build();
+        archive.memory = Some(data);
+        archive
+    }
+
+    /// Creates an `Archive` object to operate on the already open file descriptor `fd`,
+    /// e.g. a `memfd` or a temporary file that was unlinked, without knowing its path.
+    ///
+    /// The UnRAR library can only open archives by path, so the filename of the archive is
+    /// `/proc/self/fd/N` on Linux and `/dev/fd/N` on other Unix platforms, which the library
+    /// opens whenever it opens the archive. The caller retains ownership of `fd`: it must stay
+    /// open until all [`OpenArchive`]s opened from this `Archive` are dropped, and must not be
+    /// closed by them. The file must be seekable, so pipes do not work, and like for
+    /// [`from_bytes`](Archive::from_bytes), subsequent volumes of multipart archives cannot
+    /// be found.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// use std::os::fd::AsRawFd;
+    ///
+    /// let file = std::fs::File::open("data/version.rar").unwrap();
+    /// let mut archive = Archive::from_fd(file.as_raw_fd()).open_for_listing().unwrap();
+    /// assert_eq!(archive.next().unwrap().unwrap().filename.as_os_str(), "VERSION");
+    /// drop(archive);
+    /// drop(file);
+    /// ```
+    #[cfg(unix)]
+    pub fn from_fd(fd: std::os::fd::RawFd) -> Archive<'static> {
+        Archive::new_owned(crate::memory::fd_path(fd))
+    }
+
+    /// Creates an `Archive` object to operate on the already open file `handle`, without
+    /// knowing its path, like [`from_fd`](Archive::from_fd) on Unix.
+    ///
+    /// The UnRAR library can only open archives by path, so this asks Windows for the path of
+    /// the file `handle` refers to, which fails e.g. for pipes. The caller retains ownership
+    /// of `handle`: it must stay open until all [`OpenArchive`]s opened from this `Archive`
+    /// are dropped, so that the file is not deleted or renamed in the meantime.
+    #[cfg(windows)]
+    pub fn from_handle(
+        handle: std::os::windows::io::RawHandle,
+    ) -> std::io::Result<Archive<'static>> {
+        Ok(Archive::new_owned(crate::memory::handle_path(handle)?))
     }
 
     /// returns the archive's path
@@ -96,6 +291,311 @@ impl<'a> Archive<'a> {
         self.comments = Some(comments);
     }
 
+    /// Sets a callback locating volumes of a multipart archive the UnRAR library
+    /// could not find.
+    ///
+    /// `callback` receives the path where the volume was expected and returns the path
+    /// where it can be found instead, e.g. after moving or downloading it. If no volume
+    /// exists at the returned path either, `callback` is invoked again with that path.
+    /// Returning `None` aborts the operation with
+    /// [`Code::EOpen`](crate::error::Code::EOpen) and [`When::Process`](crate::error::When::Process).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use unrar::Archive;
+    /// # use std::path::Path;
+    /// let archive = Archive::new("downloads/archive.part1.rar").on_next_volume(|expected| {
+    ///     expected.file_name().map(|name| Path::new("elsewhere").join(name))
+    /// });
+    /// ```
+    pub fn on_next_volume<C>(mut self, callback: C) -> Self
+    where
+        C: FnMut(&Path) -> Option<PathBuf> + Send + 'static,
+    {
+        self.callbacks.volume = Some(Box::new(callback));
+        self
+    }
+
+    /// Sets a callback notified whenever the UnRAR library unpacked a chunk of data,
+    /// while extracting, testing or reading entries.
+    ///
+    /// `callback` receives the entry being processed and the number of bytes just
+    /// unpacked, which can be summed up and compared against its
+    /// [`unpacked_size`](crate::FileHeader::unpacked_size) to report progress.
+    /// If `callback` panics, the operation is aborted and the panic resumed afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// # use std::sync::atomic::{AtomicUsize, Ordering};
+    /// # use std::sync::Arc;
+    /// let unpacked = Arc::new(AtomicUsize::new(0));
+    /// let counter = Arc::clone(&unpacked);
+    /// let archive = Archive::new("data/version.rar")
+    ///     .on_progress(move |_entry, bytes| {
+    ///         counter.fetch_add(bytes, Ordering::Relaxed);
+    ///     });
+    /// let archive = archive.open_for_processing().unwrap().read_header().unwrap().unwrap();
+    /// let (data, _) = archive.read().unwrap();
+    /// assert_eq!(unpacked.load(Ordering::Relaxed), data.len());
+    /// ```
+    pub fn on_progress<C>(mut self, callback: C) -> Self
+    where
+        C: FnMut(&FileHeader, usize) + Send + 'static,
+    {
+        self.callbacks.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Sets a token to cancel long running operations from elsewhere, e.g. another thread.
+    ///
+    /// The token is checked whenever the UnRAR library unpacked a chunk of data.
+    /// Once it is set to `true`, the entry being extracted, tested or read is aborted
+    /// and the operation fails with [`Code::Cancelled`](crate::error::Code::Cancelled).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// # use std::sync::atomic::{AtomicBool, Ordering};
+    /// # use std::sync::Arc;
+    /// let token = Arc::new(AtomicBool::new(false));
+    /// let archive = Archive::new("data/version.rar").with_cancellation(Arc::clone(&token));
+    /// let archive = archive.open_for_processing().unwrap().read_header().unwrap().unwrap();
+    /// token.store(true, Ordering::Relaxed);
+    /// let err = archive.read().unwrap_err();
+    /// assert_eq!(err.code, unrar::error::Code::Cancelled);
+    /// ```
+    pub fn with_cancellation(mut self, token: Arc<AtomicBool>) -> Self {
+        self.callbacks.cancellation = Some(token);
+        self
+    }
+
+    /// Sets what to do when extracting an entry to a path where a file already exists.
+    /// By default, the existing file is replaced, see [`OverwritePolicy`].
+    ///
+    /// The policy is applied by [`extract`](OpenArchive::extract),
+    /// [`extract_with_base`](OpenArchive::extract_with_base),
+    /// [`extract_to`](OpenArchive::extract_to) and
+    /// [`extract_and_verify`](OpenArchive::extract_and_verify). Skipping an entry
+    /// succeeds without writing anything.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use unrar::{Archive, OverwritePolicy};
+    /// let mut archive = Archive::new("backup.rar")
+    ///     .with_overwrite(OverwritePolicy::Skip)
+    ///     .open_for_processing()
+    ///     .unwrap();
+    /// while let Some(header) = archive.read_header().unwrap() {
+    ///     // existing files are kept as they are
+    ///     archive = header.extract_with_base("restore").unwrap();
+    /// }
+    /// ```
+    pub fn with_overwrite(mut self, policy: OverwritePolicy) -> Self {
+        self.callbacks.overwrite = policy;
+        self
+    }
+
+    /// Sets whether to reject entries escaping the directory they are extracted into,
+    /// which is the default.
+    ///
+    /// Entries of untrusted archives may be named e.g. `../../.bashrc` or `/etc/passwd`
+    /// to overwrite files elsewhere. With sanitization, [`extract`](OpenArchive::extract),
+    /// [`extract_with_base`](OpenArchive::extract_with_base) and
+    /// [`extract_and_verify`](OpenArchive::extract_and_verify) fail with
+    /// [`Code::UnsafePath`] for absolute paths (including drive letters and `\\?\` prefixes on
+    /// Windows) and paths climbing out of the directory with `..`, without extracting them.
+    ///
+    /// On Windows, this also rejects paths with reserved device names like `CON`, `NUL` or
+    /// `com1.txt` and alternate data streams like `file.txt:stream`, which would not create
+    /// the file named. Other platforms allow these names, so they are extracted as is there.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// # use unrar::error::Code;
+    /// let archive = Archive::new("data/traversal.rar").open_for_processing().unwrap();
+    /// let header = archive.read_header().unwrap().unwrap();
+    /// assert_eq!(header.entry().filename.as_os_str(), "../evil.txt");
+    /// let err = header.extract_with_base("target").unwrap_err();
+    /// assert_eq!(err.code, Code::UnsafePath);
+    /// ```
+    pub fn with_sanitize(mut self, sanitize: bool) -> Self {
+        self.callbacks.unsanitized = !sanitize;
+        self
+    }
+
+    /// Limits the number of bytes unpacked from all entries together, to guard against
+    /// archives expanding to far more data than expected (decompression bombs).
+    ///
+    /// The limit is enforced while unpacking, regardless of the
+    /// [`unpacked_size`](crate::FileHeader::unpacked_size) the headers claim. Once it is
+    /// exceeded, the entry being extracted, tested or read is aborted and the operation
+    /// fails with [`Code::SizeLimitExceeded`]. Data extracted to disk up to that point is kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// # use unrar::error::Code;
+    /// let archive = Archive::new("data/version.rar").with_max_total_unpacked(4);
+    /// let archive = archive.open_for_processing().unwrap().read_header().unwrap().unwrap();
+    /// let err = archive.read().unwrap_err();
+    /// assert_eq!(err.code, Code::SizeLimitExceeded);
+    /// ```
+    pub fn with_max_total_unpacked(mut self, max: u64) -> Self {
+        self.callbacks.max_total_unpacked = Some(max);
+        self
+    }
+
+    /// Limits the number of bytes unpacked from each entry, like
+    /// [`with_max_total_unpacked`](Archive::with_max_total_unpacked) does for all entries.
+    pub fn with_max_entry_unpacked(mut self, max: u64) -> Self {
+        self.callbacks.max_entry_unpacked = Some(max);
+        self
+    }
+
+    /// Sets whether reading a corrupted entry keeps the data read so far in the error,
+    /// for recovering whatever is salvageable. Off by default.
+    ///
+    /// Applies to [`read`](OpenArchive::read) and [`read_bytes`](OpenArchive::read_bytes):
+    /// when they fail with [`Code::BadData`], the data is available from
+    /// [`UnrarError::partial_data`]. As the UnRAR library only checks the hash after unpacking,
+    /// this usually is all of the entry's data, with some of it wrong.
+    ///
+    /// When extracting to disk, the corrupted file is kept rather than deleted by the
+    /// UnRAR library (`ROADOF_KEEPBROKEN`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// # use unrar::error::Code;
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let mut bytes = std::fs::read("data/windows.rar").unwrap();
+    /// // corrupt the stored data
+    /// let pos = bytes.windows(9).position(|w| w == b"windows\r\n").unwrap();
+    /// bytes[pos] = b'W';
+    /// let path = dir.path().join("corrupted.rar");
+    /// std::fs::write(&path, bytes).unwrap();
+    ///
+    /// let archive = Archive::new(&path).with_allow_partial(true);
+    /// let header = archive.open_for_processing().unwrap().read_header().unwrap().unwrap();
+    /// let err = header.read().unwrap_err();
+    /// assert_eq!(err.code, Code::BadData);
+    /// assert_eq!(err.partial_data(), Some(&b"Windows\r\n"[..]));
+    /// ```
+    pub fn with_allow_partial(mut self, allow: bool) -> Self {
+        self.callbacks.allow_partial = allow;
+        self
+    }
+
+    /// Sets whether [`extract_all`](Archive::extract_all) goes on with the next entry after
+    /// an entry failed with corrupted data ([`Code::BadData`]), rather than stopping there.
+    /// Off by default.
+    ///
+    /// Useful to restore as much as possible from a mostly intact archive. Other failures
+    /// still stop extracting, as the library cannot go on after them, e.g. it closes the
+    /// archive when the next volume is missing ([`Code::EOpen`]).
+    pub fn with_continue_on_error(mut self, continue_on_error: bool) -> Self {
+        self.callbacks.continue_on_error = continue_on_error;
+        self
+    }
+
+    /// Sets whether [`extract_all_resolving_references`](Archive::extract_all_resolving_references)
+    /// recreates [hard links](crate::RedirectType::Hardlink) as hard links to the file their
+    /// target was extracted to. Off by default.
+    ///
+    /// The archive stores hard links without data, after their target. The UnRAR library
+    /// looks for the target relative to the working directory rather than where it was
+    /// extracted to, so it fails to create the link otherwise. A hard link whose target was
+    /// not extracted is left to the library.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let archive = Archive::new("data/hardlink.rar").with_recreate_links(true);
+    /// archive.extract_all_resolving_references(dir.path()).unwrap();
+    /// let link = std::fs::read(dir.path().join("link.txt")).unwrap();
+    /// assert_eq!(link, b"linked data\n");
+    /// ```
+    pub fn with_recreate_links(mut self, recreate_links: bool) -> Self {
+        self.callbacks.recreate_links = recreate_links;
+        self
+    }
+
+    /// Sets the number of leading components removed from the path of each entry extracted
+    /// into a directory, like `tar --strip-components`, e.g. to drop the `project-1.2.3`
+    /// directory wrapping all entries. 0 by default.
+    ///
+    /// Entries with no more components than that, like the wrapping directory itself, are
+    /// skipped. The [sanitization](Archive::with_sanitize) applies to the stripped paths.
+    /// Extracting an entry to a given file with [`extract_to`](OpenArchive::extract_to)
+    /// is unaffected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let archive = Archive::new("data/directory.rar").with_strip_components(1);
+    /// archive.extract_all(dir.path()).unwrap();
+    /// assert_eq!(std::fs::read(dir.path().join("file.txt")).unwrap(), b"hello\n");
+    /// assert!(!dir.path().join("dir").exists());
+    /// ```
+    pub fn with_strip_components(mut self, strip_components: usize) -> Self {
+        self.callbacks.strip_components = strip_components;
+        self
+    }
+
+    /// Sets whether the archive comment is read while opening the archive. Off by default.
+    ///
+    /// The UnRAR library only reads the comment while opening, so without this
+    /// [`OpenArchive::comment_bytes`] opens the archive a second time. Comments longer
+    /// than 4096 characters are still read that way.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let archive = Archive::new("data/comment.rar")
+    ///     .with_comment_on_open(true)
+    ///     .open_for_listing()
+    ///     .unwrap();
+    /// assert_eq!(archive.comment_string().unwrap(), "abcdef12345\n");
+    /// ```
+    pub fn with_comment_on_open(mut self, comment_on_open: bool) -> Self {
+        self.callbacks.comment_on_open = comment_on_open;
+        self
+    }
+
+    /// Sets how many times larger than the packed size the unpacked size of all entries may
+    /// be before a [summary](Archive::summary) flags the archive as
+    /// [suspicious](ArchiveSummary::suspicious_ratio). Defaults to
+    /// [`DEFAULT_SUSPICIOUS_RATIO`](Archive::DEFAULT_SUSPICIOUS_RATIO).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// // the data of the archive is compressed to half its size
+    /// let summary = Archive::new("data/hardlink.rar").summary().unwrap();
+    /// assert!(!summary.suspicious_ratio);
+    /// let summary = Archive::new("data/hardlink.rar").with_suspicious_ratio(1).summary().unwrap();
+    /// assert!(summary.suspicious_ratio);
+    /// ```
+    pub fn with_suspicious_ratio(mut self, ratio: u64) -> Self {
+        self.callbacks.suspicious_ratio = Some(ratio);
+        self
+    }
+
     /// Returns `true` if the filename matches a RAR archive.
     ///
     /// This method does not make any FS operations and operates purely on strings.
@@ -139,11 +639,7 @@ impl<'a> Archive<'a> {
             .and_then(|full_ext| {
                 multipart_extension().captures(&full_ext).map(|captures| {
                     let mut replacement = String::from(captures.get(1).unwrap().as_str());
-                    replacement.push_str(
-                        &repeat("?")
-                            .take(captures.get(2).unwrap().as_str().len())
-                            .collect::<String>(),
-                    );
+                    replacement.push_str(&"?".repeat(captures.get(2).unwrap().as_str().len()));
                     replacement.push_str(captures.get(3).unwrap().as_str());
                     full_ext.replace(captures.get(0).unwrap().as_str(), &replacement)
                 })
@@ -327,8 +823,9 @@ impl<'a> Archive<'a> {
     /// assert_eq!(archive.filename(), PathBuf::from("path/some.001.rar"));
     /// ```
     pub fn as_first_part(mut self) -> Self {
-        self.first_part_option()
-            .map(|fp| self.filename = Cow::Owned(fp));
+        if let Some(fp) = self.first_part_option() {
+            self.filename = Cow::Owned(fp);
+        }
         self
     }
 
@@ -338,9 +835,7 @@ impl<'a> Archive<'a> {
     ///
     /// See also: [`Process`]
     ///
-    /// # Panics
-    ///
-    /// Panics if `self.filename` contains nul values.
+    /// Fails with [`Code::ENul`] if `self.filename` contains nul values.
     pub fn open_for_processing(self) -> UnrarResult<OpenArchive<Process, CursorBeforeHeader>> {
         self.open(None)
     }
@@ -349,9 +844,7 @@ impl<'a> Archive<'a> {
     ///
     /// See also: [`List`]
     ///
-    /// # Panics
-    ///
-    /// Panics if `self.filename` contains nul values.
+    /// Fails with [`Code::ENul`] if `self.filename` contains nul values.
     pub fn open_for_listing(self) -> UnrarResult<OpenArchive<List, CursorBeforeHeader>> {
         self.open(None)
     }
@@ -363,24 +856,412 @@ impl<'a> Archive<'a> {
     ///
     /// See also: [`ListSplit`]
     ///
-    /// # Panics
-    ///
-    /// Panics if `self.filename` contains nul values.
-
+    /// Fails with [`Code::ENul`] if `self.filename` contains nul values.
     pub fn open_for_listing_split(self) -> UnrarResult<OpenArchive<ListSplit, CursorBeforeHeader>> {
         self.open(None)
     }
 
-    /// Opens the underlying archive with the provided parameters.
+    /// Lists all entries of the archive, see [`open_for_listing`](Archive::open_for_listing).
+    ///
+    /// Fails with the first error encountered, including a missing volume.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let entries = Archive::new("data/version.rar").list().unwrap();
+    /// assert_eq!(entries.len(), 1);
+    /// assert_eq!(entries[0].filename.as_os_str(), "VERSION");
+    /// ```
+    pub fn list(self) -> UnrarResult<Vec<FileHeader>> {
+        self.open_for_listing()?.collect()
+    }
+
+    /// Counts the entries by listing the archive, counting entries split across volumes once.
+    ///
+    /// RAR archives store no entry count: the locator of the RAR5 main header only holds
+    /// the offsets of the quick open data and recovery record. Listing only reads the headers
+    /// and seeks past the data, which the UnRAR library speeds up with the quick open data
+    /// (`rar -qo`) if the archive has some, see [`OpenArchive::has_quick_open`].
+    ///
+    /// Fails with the first error encountered, including a missing volume.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// assert_eq!(Archive::new("data/directory.rar").count_entries().unwrap(), 3);
+    /// ```
+    pub fn count_entries(self) -> UnrarResult<usize> {
+        self.open_for_listing()?
+            .try_fold(0, |count, entry| entry.map(|_| count + 1))
+    }
+
+    /// Lists all entries of a multipart archive grouped by the volume they are in,
+    /// see [`open_for_listing_split`](Archive::open_for_listing_split). An entry split
+    /// across volumes appears in each of them.
+    ///
+    /// Fails with the first error encountered, including a missing volume.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let volumes = Archive::new("data/volumes.part1.rar").list_split().unwrap();
+    /// let names: Vec<Vec<_>> = volumes
+    ///     .iter()
+    ///     .map(|entries| entries.iter().map(|e| e.filename.to_str().unwrap()).collect())
+    ///     .collect();
+    /// assert_eq!(names, [vec!["a.txt", "b.txt"], vec!["b.txt"], vec!["b.txt", "c.txt"]]);
+    /// ```
+    pub fn list_split(self) -> UnrarResult<Vec<Vec<FileHeader>>> {
+        let mut volumes: Vec<Vec<FileHeader>> = Vec::new();
+        for entry in self.open_for_listing_split()? {
+            let entry = entry?;
+            match volumes.last_mut() {
+                Some(volume) if volume[0].volume == entry.volume => volume.push(entry),
+                _ => volumes.push(vec![entry]),
+            }
+        }
+        Ok(volumes)
+    }
+
+    /// Lists all entries in a single pass and sums them up, see [`ArchiveSummary`].
+    ///
+    /// Fails with the first error encountered, including a missing volume.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let summary = Archive::new("data/directory.rar").summary().unwrap();
+    /// assert_eq!(summary.entries, 3);
+    /// assert_eq!(summary.directories, 2);
+    /// assert_eq!(summary.unpacked_size, 6);
+    /// assert!(!summary.is_solid);
+    /// ```
+    pub fn summary(self) -> UnrarResult<ArchiveSummary> {
+        let ratio = self
+            .callbacks
+            .suspicious_ratio
+            .unwrap_or(Self::DEFAULT_SUSPICIOUS_RATIO);
+        let archive = self.open_for_listing_split()?;
+        let mut summary = ArchiveSummary {
+            entries: 0,
+            directories: 0,
+            unpacked_size: 0,
+            packed_size: 0,
+            has_encrypted_entries: false,
+            is_solid: archive.is_solid(),
+            is_volume: archive.is_volume(),
+            has_encrypted_headers: archive.has_encrypted_headers(),
+            suspicious_ratio: false,
+        };
+        for entry in archive {
+            let entry = entry?;
+            // listing each volume's segments to get all of the packed size
+            summary.packed_size += entry.packed_size();
+            if entry.is_split_before() {
+                continue;
+            }
+            summary.entries += 1;
+            summary.directories += entry.is_directory() as usize;
+            summary.unpacked_size += entry.unpacked_size;
+            summary.has_encrypted_entries |= entry.is_encrypted();
+        }
+        summary.suspicious_ratio =
+            summary.unpacked_size > summary.packed_size.saturating_mul(ratio);
+        Ok(summary)
+    }
+
+    /// Reads the entry named `name` into a `Vec<u8>`, skipping over all entries before it.
+    /// In a [solid](OpenArchive::is_solid) archive, skipping means decompressing them.
+    ///
+    /// Fails with [`Code::EntryNotFound`] if the archive contains no such entry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let data = Archive::new("data/directory.rar").extract_file("dir/file.txt").unwrap();
+    /// assert_eq!(data, b"hello\n");
+    /// ```
+    pub fn extract_file<P: AsRef<Path>>(self, name: P) -> UnrarResult<Vec<u8>> {
+        let (data, _) = self.find_entry(name.as_ref())?.read()?;
+        Ok(data)
+    }
+
+    /// Extracts the entry named `name` into the file `dest`, like
+    /// [`extract_to`](OpenArchive::extract_to), skipping over all entries before it.
     ///
-    /// # Panics
+    /// Fails with [`Code::EntryNotFound`] if the archive contains no such entry.
+    pub fn extract_file_to<P: AsRef<Path>, D: AsRef<Path>>(
+        self,
+        name: P,
+        dest: D,
+    ) -> UnrarResult<()> {
+        self.find_entry(name.as_ref())?.extract_to(dest)?;
+        Ok(())
+    }
+
+    /// Extracts all entries into `base` in the order they are stored, like
+    /// [`extract_with_base`](OpenArchive::extract_with_base), returning each entry
+    /// along with the result of extracting it, telling whether it was written, skipped or
+    /// renamed according to the [overwrite policy](Archive::with_overwrite).
+    ///
+    /// Extracting stops after the first entry that failed, unless
+    /// [`with_continue_on_error`](Archive::with_continue_on_error) is set and
+    /// the library can go on with the next entry.
+    ///
+    /// Directory entries are created even if they are empty, and their modification time is
+    /// restored once all entries are extracted, as in [`OpenArchive::extract_all_to`].
+    ///
+    /// Fails if the archive cannot be opened or a header cannot be read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let entries = Archive::new("data/directory.rar")
+    ///     .with_continue_on_error(true)
+    ///     .extract_all(dir.path())
+    ///     .unwrap();
+    /// assert_eq!(entries.len(), 3);
+    /// assert!(entries.iter().all(|(_, result)| result.is_ok()));
+    /// ```
+    pub fn extract_all<P: AsRef<Path>>(
+        self,
+        base: P,
+    ) -> UnrarResult<Vec<(FileHeader, UnrarResult<ExtractOutcome>)>> {
+        let base = base.as_ref();
+        let mut entries = Vec::new();
+        let mut dirs = Vec::new();
+        let mut archive = self.open_for_processing()?;
+        while let Some(header) = archive.read_header()? {
+            let (entry, result, rest) = header.extract_continuing(base, &mut dirs);
+            entries.push((entry, result));
+            match rest {
+                Some(rest) => archive = rest,
+                None => break,
+            }
+        }
+        restore_dir_times(&dirs);
+        Ok(entries)
+    }
+
+    /// Extracts all entries into `base` in the order they are stored, like
+    /// [`extract_with_base`](OpenArchive::extract_with_base).
+    ///
+    /// [File references](FileHeader::is_reference) are created as copies of the file their
+    /// source was extracted to, even if it was [renamed](OverwritePolicy::Rename), rather than
+    /// failing with [`Code::EReference`]. The archive stores sources before their references,
+    /// so extracting everything in order is enough to resolve them. Hard links are resolved
+    /// the same way if [`with_recreate_links`](Archive::with_recreate_links) is set.
+    ///
+    /// Fails with the first error encountered, or with [`Code::Unsupported`] if the UnRAR
+    /// library is too old to report links, see [`supports_rar5`](crate::supports_rar5).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let dir = tempfile::tempdir().unwrap();
+    /// Archive::new("data/reference.rar").extract_all_resolving_references(dir.path()).unwrap();
+    /// let copy = std::fs::read(dir.path().join("copy.txt")).unwrap();
+    /// assert_eq!(copy, b"referenced data\n");
+    /// ```
+    pub fn extract_all_resolving_references<P: AsRef<Path>>(self, base: P) -> UnrarResult<()> {
+        dll::require_rar5(When::Open)?;
+        let base = base.as_ref();
+        let mut extracted = HashMap::new();
+        let mut archive = self.open_for_processing()?;
+        while let Some(header) = archive.read_header()? {
+            archive = header.extract_resolving_references(base, &mut extracted)?;
+        }
+        Ok(())
+    }
+
+    /// Tests all entries by unpacking them without writing anything, like `unrar t`.
     ///
-    /// Panics if `path` contains nul values.
+    /// Entries with corrupted data fail with [`Code::BadData`] and testing goes on with
+    /// the next one. Any other failure, e.g. a wrong password or a missing volume, is
+    /// recorded for its entry and ends the test, as the library cannot go on after it.
+    ///
+    /// Fails if the archive cannot be opened or a header cannot be read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let report = Archive::new("data/directory.rar").test().unwrap();
+    /// assert!(report.passed());
+    /// assert_eq!(report.entries.len(), 3);
+    /// ```
+    pub fn test(self) -> UnrarResult<TestReport> {
+        let mut entries = Vec::new();
+        let mut archive = self.open_for_processing()?;
+        while let Some(header) = archive.read_header()? {
+            let (entry, result, rest) = header.test_continuing();
+            entries.push((entry, result));
+            match rest {
+                Some(rest) => archive = rest,
+                None => break,
+            }
+        }
+        Ok(TestReport { entries })
+    }
+
+    /// opens the archive for processing and skips to the entry named `name`.
+    fn find_entry(self, name: &Path) -> UnrarResult<OpenArchive<Process, CursorBeforeFile>> {
+        self.open_for_processing()?.seek_to(name)
+    }
+
+    /// Computes the name of the first volume of the multipart archive `any_part` is a volume of,
+    /// which extracting has to start with, see [`OpenArchive::is_first_volume`].
+    ///
+    /// Unlike [`first_part_option`](Archive::first_part_option), this reads the
+    /// [naming scheme](OpenArchive::volume_naming) from `any_part`, so the first volume of
+    /// `name.r03` is `name.rar`. Returns `None` if `any_part` cannot be opened or is not a
+    /// volume. Whether the first volume exists is not checked.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// # use std::path::PathBuf;
+    /// let first = Archive::first_volume_path("data/volumes.part3.rar").unwrap();
+    /// assert_eq!(first, PathBuf::from("data/volumes.part1.rar"));
+    /// let first = Archive::first_volume_path("data/oldnaming.r01").unwrap();
+    /// assert_eq!(first, PathBuf::from("data/oldnaming.rar"));
+    /// assert_eq!(Archive::first_volume_path("data/version.rar"), None);
+    /// ```
+    pub fn first_volume_path<P: AsRef<Path>>(any_part: P) -> Option<PathBuf> {
+        let archive = Archive::new(any_part.as_ref()).open_for_listing().ok()?;
+        archive.first_volume_path()
+    }
+
+    /// Tells whether the archive at `path` needs a password to even be listed, i.e. whether
+    /// its [headers are encrypted](OpenArchive::has_encrypted_headers), by trying to read the
+    /// first header without a password. The archive is closed again before returning.
+    ///
+    /// Archives whose headers are not encrypted can still contain
+    /// [encrypted entries](FileHeader::is_encrypted), which then need a password to be
+    /// extracted. Fails with the error opening or reading the archive, e.g. [`Code::EOpen`]
+    /// if it does not exist, unless it is [`Code::MissingPassword`] or [`Code::BadPassword`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// assert!(Archive::needs_password("data/comment-hpw-password.rar").unwrap());
+    /// // only the entries are encrypted
+    /// assert!(!Archive::needs_password("data/crypted.rar").unwrap());
+    /// assert!(!Archive::needs_password("data/version.rar").unwrap());
+    /// ```
+    pub fn needs_password<P: AsRef<Path>>(path: P) -> UnrarResult<bool> {
+        let first = Archive::new(path.as_ref())
+            .open_for_listing()
+            .and_then(|archive| archive.read_header());
+        match first {
+            Ok(_) => Ok(false),
+            Err(e) if matches!(e.code, Code::MissingPassword | Code::BadPassword) => Ok(true),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Finds the volumes of the multipart archive starting with the volume `first` by applying
+    /// the naming scheme of the UnRAR library: `name.part1.rar`, `name.part2.rar`, … or for
+    /// the old naming `name.rar`, `name.r00`, `name.r01`, …
+    ///
+    /// The [naming scheme](OpenArchive::volume_naming) is read from `first`. Like the UnRAR
+    /// library, volumes of the new naming renamed to the old one are found as well.
+    /// Stops at the first missing volume, then reads the last volume found to tell whether
+    /// the archive continues after it. For a single-part archive, this is just `first`.
+    ///
+    /// Fails if `first` or the last volume found cannot be read, e.g. with
+    /// [`Code::MissingPassword`] if the headers are encrypted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// # use std::path::PathBuf;
+    /// let volumes = Archive::volume_paths("data/volumes.part1.rar").unwrap();
+    /// assert_eq!(volumes.paths.len(), 3);
+    /// assert_eq!(volumes.paths[2], PathBuf::from("data/volumes.part3.rar"));
+    /// assert!(volumes.complete);
+    /// ```
+    pub fn volume_paths<P: AsRef<Path>>(first: P) -> UnrarResult<VolumePaths> {
+        let first = first.as_ref();
+        let archive = Archive::new(first).open_for_listing()?;
+        let Some(naming) = archive.volume_naming() else {
+            return Ok(VolumePaths {
+                paths: vec![first.to_path_buf()],
+                complete: true,
+                naming: None,
+            });
+        };
+        let (paths, naming) = find_volumes(first.to_path_buf(), naming);
+        // listing the last volume asks for the next one if the archive continues
+        let asked = Arc::new(AtomicBool::new(false));
+        let volume = Archive::new(paths.last().unwrap()).on_next_volume({
+            let asked = Arc::clone(&asked);
+            move |_| {
+                asked.store(true, Ordering::Relaxed);
+                None
+            }
+        });
+        let result = volume
+            .open_for_listing_split()?
+            .try_for_each(|entry| entry.map(drop));
+        let complete = match result {
+            Err(_) if asked.load(Ordering::Relaxed) => false,
+            Err(e) => return Err(e),
+            Ok(()) => true,
+        };
+        Ok(VolumePaths {
+            paths,
+            complete,
+            naming: Some(naming),
+        })
+    }
+
+    /// Sums the sizes on disk of the volumes found by [`volume_paths`](Archive::volume_paths),
+    /// i.e. the size of the whole archive including its headers, unlike the
+    /// [packed sizes](FileHeader::packed_size) of its entries.
+    ///
+    /// Only the volumes up to the first missing one are counted, check
+    /// [`VolumePaths::complete`] to tell whether that is all of them.
+    /// Fails like `volume_paths`, or with [`Code::EOpen`] if the size of a volume
+    /// cannot be read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let size = Archive::total_volume_bytes("data/volumes.part1.rar").unwrap();
+    /// assert_eq!(size, 88 + 58 + 88);
+    /// ```
+    pub fn total_volume_bytes<P: AsRef<Path>>(first: P) -> UnrarResult<u64> {
+        Archive::volume_paths(first)?
+            .paths
+            .iter()
+            .try_fold(0, |total, volume| match std::fs::metadata(volume) {
+                Ok(metadata) => Ok(total + metadata.len()),
+                Err(e) => Err(UnrarError::from(Code::EOpen, When::Open).with_source(e)),
+            })
+    }
+
+    /// Opens the underlying archive with the provided parameters.
     fn open<M: OpenMode>(
         self,
         recover: Option<&mut Option<OpenArchive<M, CursorBeforeHeader>>>,
     ) -> UnrarResult<OpenArchive<M, CursorBeforeHeader>> {
-        OpenArchive::new(&self.filename, self.password, recover)
+        match self.memory {
+            Some(data) => OpenArchive::from_memory(data, self.password, self.callbacks, recover),
+            None => OpenArchive::new(&self.filename, self.password, self.callbacks, recover),
+        }
     }
 
     /// Opens the underlying archive with the provided OpenMode,
@@ -415,9 +1296,7 @@ impl<'a> Archive<'a> {
     /// # }
     /// ```
     ///
-    /// # Panics
-    ///
-    /// Panics if `path` contains nul values.
+    /// Fails with [`Code::ENul`] if `self.filename` contains nul values.
     pub fn break_open<M: OpenMode>(
         self,
         error: Option<&mut Option<UnrarError>>,
@@ -426,7 +1305,9 @@ impl<'a> Archive<'a> {
         self.open(Some(&mut recovered))
             .or_else(|x| match recovered {
                 Some(archive) => {
-                    error.map(|error| *error = Some(x));
+                    if let Some(error) = error {
+                        *error = Some(x);
+                    }
                     Ok(archive)
                 }
                 None => Err(x),
@@ -434,6 +1315,201 @@ impl<'a> Archive<'a> {
     }
 }
 
+#[cfg(feature = "tokio")]
+impl Archive<'static> {
+    /// Reads the entry named `name` like [`extract_file`](Self::extract_file), but on tokio's
+    /// blocking thread pool, so the async task awaiting it does not block its executor while
+    /// the UnRAR library unpacks the data. Must be called within a tokio runtime.
+    ///
+    /// The options set on the archive apply. In particular, setting the
+    /// [cancellation token](Self::with_cancellation) from another task or thread stops
+    /// unpacking, failing with [`Code::Cancelled`], which it also fails with if the runtime
+    /// shuts down first. A panic in a callback is resumed in the awaiting task.
+    ///
+    /// Only available with the `tokio` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// # let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// # runtime.block_on(async {
+    /// let archive = Archive::new_owned("data/directory.rar");
+    /// let data = archive.extract_file_async("dir/file.txt").await.unwrap();
+    /// assert_eq!(data, b"hello\n");
+    /// # });
+    /// ```
+    pub async fn extract_file_async<P: Into<PathBuf>>(self, name: P) -> UnrarResult<Vec<u8>> {
+        let name = name.into();
+        match tokio::task::spawn_blocking(move || self.extract_file(name)).await {
+            Ok(result) => result,
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(_) => Err(UnrarError::from(Code::Cancelled, When::Process)),
+        }
+    }
+}
+
+/// Reads the entry named `name` from the archive at `path` on tokio's blocking thread pool,
+/// see [`Archive::extract_file_async`].
+///
+/// Only available with the `tokio` feature.
+#[cfg(feature = "tokio")]
+pub async fn extract_file_async<P, N>(path: P, name: N) -> UnrarResult<Vec<u8>>
+where
+    P: Into<PathBuf>,
+    N: Into<PathBuf>,
+{
+    Archive::new_owned(path).extract_file_async(name).await
+}
+
+/// Configures how to open a RAR archive, created with [`Archive::builder`].
+///
+/// The methods correspond to the constructors and setters of [`Archive`], which
+/// [`build`](ArchiveBuilder::build) returns. For the common case, the archive can be
+/// opened right away with [`list`](ArchiveBuilder::list) or [`process`](ArchiveBuilder::process).
+pub struct ArchiveBuilder<'a> {
+    archive: Archive<'a>,
+}
+
+impl<'a> ArchiveBuilder<'a> {
+    fn new(filename: Cow<'a, Path>) -> Self {
+        ArchiveBuilder {
+            archive: Archive {
+                filename,
+                password: None,
+                comments: None,
+                memory: None,
+                callbacks: Callbacks::default(),
+            },
+        }
+    }
+
+    /// Sets the password of an encrypted archive, see [`Archive::with_password`].
+    pub fn password<Pw>(mut self, password: &'a Pw) -> Self
+    where
+        Pw: AsRef<[u8]> + ?Sized,
+    {
+        self.archive.password = Some(password.as_ref());
+        self
+    }
+
+    /// Sets a callback supplying the password once it is needed,
+    /// see [`Archive::with_password_callback`].
+    pub fn password_callback<C>(mut self, callback: C) -> Self
+    where
+        C: FnMut(usize) -> Option<Vec<u8>> + Send + 'static,
+    {
+        self.archive.callbacks.password = Some(Box::new(callback));
+        self
+    }
+
+    /// Sets a callback locating missing volumes, see [`Archive::on_next_volume`].
+    pub fn on_next_volume<C>(mut self, callback: C) -> Self
+    where
+        C: FnMut(&Path) -> Option<PathBuf> + Send + 'static,
+    {
+        self.archive = self.archive.on_next_volume(callback);
+        self
+    }
+
+    /// Sets a callback notified about unpacked data, see [`Archive::on_progress`].
+    pub fn on_progress<C>(mut self, callback: C) -> Self
+    where
+        C: FnMut(&FileHeader, usize) + Send + 'static,
+    {
+        self.archive = self.archive.on_progress(callback);
+        self
+    }
+
+    /// Sets a token to cancel long running operations, see [`Archive::with_cancellation`].
+    pub fn with_cancellation(mut self, token: Arc<AtomicBool>) -> Self {
+        self.archive = self.archive.with_cancellation(token);
+        self
+    }
+
+    /// Sets what to do when extracting over existing files, see [`Archive::with_overwrite`].
+    pub fn overwrite(mut self, policy: OverwritePolicy) -> Self {
+        self.archive = self.archive.with_overwrite(policy);
+        self
+    }
+
+    /// Sets whether to reject entries escaping the extraction directory,
+    /// see [`Archive::with_sanitize`].
+    pub fn sanitize(mut self, sanitize: bool) -> Self {
+        self.archive = self.archive.with_sanitize(sanitize);
+        self
+    }
+
+    /// Limits the bytes unpacked from all entries, see [`Archive::with_max_total_unpacked`].
+    pub fn max_total_unpacked(mut self, max: u64) -> Self {
+        self.archive = self.archive.with_max_total_unpacked(max);
+        self
+    }
+
+    /// Limits the bytes unpacked from each entry, see [`Archive::with_max_entry_unpacked`].
+    pub fn max_entry_unpacked(mut self, max: u64) -> Self {
+        self.archive = self.archive.with_max_entry_unpacked(max);
+        self
+    }
+
+    /// Keeps the data read from corrupted entries, see [`Archive::with_allow_partial`].
+    pub fn allow_partial(mut self, allow: bool) -> Self {
+        self.archive = self.archive.with_allow_partial(allow);
+        self
+    }
+
+    /// Goes on extracting after a failed entry, see [`Archive::with_continue_on_error`].
+    pub fn continue_on_error(mut self, continue_on_error: bool) -> Self {
+        self.archive = self.archive.with_continue_on_error(continue_on_error);
+        self
+    }
+
+    /// Creates hard links to the extracted files, see [`Archive::with_recreate_links`].
+    pub fn recreate_links(mut self, recreate_links: bool) -> Self {
+        self.archive = self.archive.with_recreate_links(recreate_links);
+        self
+    }
+
+    /// Removes leading path components when extracting, see [`Archive::with_strip_components`].
+    pub fn strip_components(mut self, strip_components: usize) -> Self {
+        self.archive = self.archive.with_strip_components(strip_components);
+        self
+    }
+
+    /// Reads the archive comment while opening, see [`Archive::with_comment_on_open`].
+    pub fn comment_on_open(mut self, comment_on_open: bool) -> Self {
+        self.archive = self.archive.with_comment_on_open(comment_on_open);
+        self
+    }
+
+    /// Sets the ratio beyond which a summary is suspicious, see [`Archive::with_suspicious_ratio`].
+    pub fn suspicious_ratio(mut self, ratio: u64) -> Self {
+        self.archive = self.archive.with_suspicious_ratio(ratio);
+        self
+    }
+
+    /// Returns the configured [`Archive`], e.g. to open it with [`Archive::break_open`].
+    pub fn build(self) -> Archive<'a> {
+        self.archive
+    }
+
+    /// Opens the archive for listing its entries, see [`Archive::open_for_listing`].
+    pub fn list(self) -> UnrarResult<OpenArchive<List, CursorBeforeHeader>> {
+        self.archive.open_for_listing()
+    }
+
+    /// Opens the archive for listing its entries without omitting or pooling split entries,
+    /// see [`Archive::open_for_listing_split`].
+    pub fn list_split(self) -> UnrarResult<OpenArchive<ListSplit, CursorBeforeHeader>> {
+        self.archive.open_for_listing_split()
+    }
+
+    /// Opens the archive for processing its entries, see [`Archive::open_for_processing`].
+    pub fn process(self) -> UnrarResult<OpenArchive<Process, CursorBeforeHeader>> {
+        self.archive.open_for_processing()
+    }
+}
+
 fn get_rar_extension<T: AsRef<Path>>(path: T) -> Option<String> {
     path.as_ref().extension().and_then(|ext| {
         let pre_ext = path
@@ -455,6 +1531,96 @@ pub fn is_multipart(s: &Path) -> bool {
     get_rar_extension(s).is_some_and(|e| multipart_extension().is_match(&e))
 }
 
+/// the volumes on disk starting with `first`, up to the first missing one, along with the
+/// naming they were found with. Like `MergeArchive` in the UnRAR library, this falls back
+/// to the old naming for volumes of the new one that were renamed.
+pub(crate) fn find_volumes(
+    first: PathBuf,
+    mut naming: VolumeNaming,
+) -> (Vec<PathBuf>, VolumeNaming) {
+    let mut paths = vec![first];
+    loop {
+        let last = paths.last().unwrap();
+        let next = match next_volume(last, naming).filter(|next| next.exists()) {
+            None if naming == VolumeNaming::New => next_volume(last, VolumeNaming::Old)
+                .filter(|next| next.exists())
+                .inspect(|_| naming = VolumeNaming::Old),
+            next => next,
+        };
+        match next {
+            Some(next) => paths.push(next),
+            None => return (paths, naming),
+        }
+    }
+}
+
+/// the name of the volume following `volume` like `NextVolumeName` in the UnRAR library, e.g.
+/// `name.part10.rar` after `name.part09.rar` or, with the old naming, `name.r00` after `name.rar`
+/// and `name.s00` after `name.r99`. Returns `None` if no such name can be formed.
+fn next_volume(volume: &Path, naming: VolumeNaming) -> Option<PathBuf> {
+    let mut name = volume.file_name()?.to_str()?.as_bytes().to_vec();
+    let dot = match name.iter().rposition(|&c| c == b'.') {
+        Some(dot) => {
+            let ext = &name[dot + 1..];
+            if ext.is_empty()
+                || ext.eq_ignore_ascii_case(b"exe")
+                || ext.eq_ignore_ascii_case(b"sfx")
+            {
+                name.truncate(dot + 1);
+                name.extend_from_slice(b"rar");
+            }
+            dot
+        }
+        None => {
+            name.extend_from_slice(b".rar");
+            name.len() - 4
+        }
+    };
+    if naming == VolumeNaming::New {
+        // increments the last number, carrying over into a new digit for `.part9.rar`
+        let mut pos = name.iter().rposition(u8::is_ascii_digit)?;
+        loop {
+            if name[pos] != b'9' {
+                name[pos] += 1;
+                break;
+            }
+            name[pos] = b'0';
+            if pos == 0 || !name[pos - 1].is_ascii_digit() {
+                name.insert(pos, b'1');
+                break;
+            }
+            pos -= 1;
+        }
+    } else {
+        if name.len() - dot < 3 {
+            name.truncate(dot + 1);
+            name.extend_from_slice(b"rar");
+        }
+        if !(name[dot + 2].is_ascii_digit() && name.get(dot + 3).is_some_and(u8::is_ascii_digit)) {
+            name.truncate(dot + 2);
+            name.extend_from_slice(b"00");
+        } else {
+            // increments the extension, carrying over into its first character
+            let mut pos = name.len() - 1;
+            loop {
+                if name[pos] != b'9' {
+                    name[pos] += 1;
+                    break;
+                }
+                if name[pos - 1] == b'.' {
+                    name[pos] = b'a';
+                    break;
+                }
+                name[pos] = b'0';
+                pos -= 1;
+            }
+        }
+    }
+    String::from_utf8(name)
+        .ok()
+        .map(|name| volume.with_file_name(name))
+}
+
 #[cfg(test)]
 mod tests {
     use super::Archive;
@@ -526,18 +1692,34 @@ mod tests {
         assert_eq!(Archive::new("v8/v8").first_part(), PathBuf::from("v8/v8"));
     }
 
+    #[test]
+    fn next_volume() {
+        use crate::VolumeNaming::{New, Old};
+        let next = |name: &str, naming| {
+            super::next_volume(name.as_ref(), naming).map(|next| next.into_os_string())
+        };
+        assert_eq!(next("dir/arc.part1.rar", New).unwrap(), "dir/arc.part2.rar");
+        assert_eq!(next("arc.part09.rar", New).unwrap(), "arc.part10.rar");
+        assert_eq!(next("arc.part9.rar", New).unwrap(), "arc.part10.rar");
+        assert_eq!(next("arc.part1.exe", New).unwrap(), "arc.part2.rar");
+        assert_eq!(next("arc.rar", New), None);
+        assert_eq!(next("arc.rar", Old).unwrap(), "arc.r00");
+        assert_eq!(next("arc.r00", Old).unwrap(), "arc.r01");
+        assert_eq!(next("arc.r99", Old).unwrap(), "arc.s00");
+        assert_eq!(next("arc.001", Old).unwrap(), "arc.002");
+        assert_eq!(next("arc.999", Old).unwrap(), "arc.a00");
+        assert_eq!(next("arc", Old).unwrap(), "arc.r00");
+    }
+
     #[test]
     fn is_archive() {
-        assert_eq!(super::is_archive(&PathBuf::from("archive.rar")), true);
-        assert_eq!(super::is_archive(&PathBuf::from("archive.part1.rar")), true);
-        assert_eq!(
-            super::is_archive(&PathBuf::from("archive.part100.rar")),
-            true
-        );
-        assert_eq!(super::is_archive(&PathBuf::from("archive.r10")), true);
-        assert_eq!(super::is_archive(&PathBuf::from("archive.part1rar")), false);
-        assert_eq!(super::is_archive(&PathBuf::from("archive.rar\n")), false);
-        assert_eq!(super::is_archive(&PathBuf::from("archive.zip")), false);
+        assert!(super::is_archive(&PathBuf::from("archive.rar")));
+        assert!(super::is_archive(&PathBuf::from("archive.part1.rar")));
+        assert!(super::is_archive(&PathBuf::from("archive.part100.rar")));
+        assert!(super::is_archive(&PathBuf::from("archive.r10")));
+        assert!(!super::is_archive(&PathBuf::from("archive.part1rar")));
+        assert!(!super::is_archive(&PathBuf::from("archive.rar\n")));
+        assert!(!super::is_archive(&PathBuf::from("archive.zip")));
     }
 
     #[test]
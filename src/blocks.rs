@@ -0,0 +1,511 @@
+//! Walking the block headers of an archive file, to tell a truncated archive apart from
+//! one the UnRAR library read to its end, and to read what the library does not report.
+
+use crate::open_archive::{
+    find_signature, EncryptionInfo, EncryptionMethod, RarFormat, MAX_SFX_SIZE,
+};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// `HEAD_MAIN` of RAR5 archives
+const HEAD5_MAIN: u64 = 1;
+/// `HEAD_FILE` of RAR5 archives
+const HEAD5_FILE: u64 = 2;
+/// `HEAD_ENDARC` of RAR5 archives
+const HEAD5_ENDARC: u64 = 5;
+/// `HEAD_CRYPT` of RAR5 archives, the headers after it are encrypted
+const HEAD5_CRYPT: u64 = 4;
+/// `MHEXTRA_LOCATOR` of RAR5 main headers, the position of the quick open record
+const MHEXTRA_LOCATOR: u64 = 1;
+/// `MHEXTRA_LOCATOR_QLIST` of the locator, followed by the quick open offset
+const MHEXTRA_LOCATOR_QLIST: u64 = 1;
+/// `FHEXTRA_CRYPT` of RAR5 file headers, the encryption of the entry
+const FHEXTRA_CRYPT: u64 = 1;
+/// `FHFL_DIRECTORY` of RAR5 file flags
+const FHFL_DIRECTORY: u64 = 0x1;
+/// `FHFL_UTIME` of RAR5 file flags, followed by the modification time
+const FHFL_UTIME: u64 = 0x2;
+/// `FHFL_CRC32` of RAR5 file flags, followed by the CRC32 of the data
+const FHFL_CRC32: u64 = 0x4;
+/// `HEAD3_MAIN` of RAR4 archives
+const HEAD3_MAIN: u8 = 0x73;
+/// `HEAD3_FILE` of RAR4 archives
+const HEAD3_FILE: u8 = 0x74;
+/// `HEAD3_SERVICE` of RAR4 archives, laid out like file headers
+const HEAD3_SERVICE: u8 = 0x7a;
+/// `HEAD3_ENDARC` of RAR4 archives
+const HEAD3_ENDARC: u8 = 0x7b;
+/// `MHD_PASSWORD` of RAR4 main headers, the headers after it are encrypted
+const MHD_PASSWORD: u16 = 0x80;
+/// `LHD_PASSWORD` of RAR4 file headers, the entry is encrypted
+const LHD_PASSWORD: u16 = 0x4;
+/// `LONG_BLOCK` of RAR4 headers, followed by the size of the data
+const LONG_BLOCK: u16 = 0x8000;
+/// `LHD_LARGE` of RAR4 file headers, which store the high 32 bits of the data size
+const LHD_LARGE: u16 = 0x100;
+
+/// whether the archive at `path` ends in the middle of a block, before its end of archive
+/// header, the way `Archive::UnexpEndArcMsg` in the UnRAR sources tells an unexpected end
+/// of archive.
+///
+/// `false` if it cannot be told: for RAR 1.4 archives, encrypted headers, damaged headers
+/// or if the file cannot be read. Like the UnRAR library, an archive without an end of
+/// archive header is not truncated if its last block ends with the file.
+pub(crate) fn is_truncated(path: &Path) -> bool {
+    std::fs::File::open(path)
+        .and_then(|file| ends_early(io::BufReader::new(file)))
+        .unwrap_or(false)
+}
+
+/// whether the RAR5 archive at `path` has a quick open record, i.e. its main header locates
+/// one, see `Archive::ProcessExtra50` in the UnRAR sources.
+///
+/// `false` for older archives, which have none, encrypted headers or if the file cannot be read.
+pub(crate) fn has_quick_open(path: &Path) -> bool {
+    std::fs::File::open(path)
+        .and_then(|file| locates_quick_open(io::BufReader::new(file)))
+        .unwrap_or(false)
+}
+
+fn locates_quick_open<R: Read + Seek>(mut file: R) -> io::Result<bool> {
+    let mut start = Vec::new();
+    (&mut file).take(MAX_SFX_SIZE).read_to_end(&mut start)?;
+    let offset = match find_signature(&start) {
+        Some((offset, RarFormat::Rar50)) => offset + 8,
+        _ => return Ok(false),
+    };
+    file.seek(SeekFrom::Start(offset as u64))?;
+    file.read_exact(&mut [0; 4])?;
+    let (size, _) = vint(&mut file)?;
+    let mut header = Vec::new();
+    (&mut file).take(size).read_to_end(&mut header)?;
+    let mut fields = header.as_slice();
+    let (kind, _) = vint(&mut fields)?;
+    let (flags, _) = vint(&mut fields)?;
+    if kind != HEAD5_MAIN || flags & 0x1 == 0 {
+        return Ok(false);
+    }
+    let (extra, _) = vint(&mut fields)?;
+    let mut extra = match header.len().checked_sub(extra as usize) {
+        Some(start) => &header[start..],
+        None => return Ok(false),
+    };
+    while !extra.is_empty() {
+        let (size, _) = vint(&mut extra)?;
+        let (mut record, rest) = extra.split_at(extra.len().min(size as usize));
+        extra = rest;
+        if vint(&mut record)?.0 == MHEXTRA_LOCATOR {
+            let (flags, _) = vint(&mut record)?;
+            // an offset of 0 means there was no room to store it
+            return Ok(flags & MHEXTRA_LOCATOR_QLIST != 0 && vint(&mut record)?.0 != 0);
+        }
+    }
+    Ok(false)
+}
+
+/// the largest dictionary the entries of the RAR5 archive at `path` were compressed with, as
+/// `Archive::ReadHeader50` in the UnRAR sources computes it from the compression information.
+///
+/// `None` for older archives, which store no dictionary size in their headers, encrypted
+/// headers, archives without files, damaged headers or if the file cannot be read.
+pub(crate) fn dictionary_size(path: &Path) -> Option<u64> {
+    let mut file = io::BufReader::new(std::fs::File::open(path).ok()?);
+    let len = file.seek(SeekFrom::End(0)).ok()?;
+    file.seek(SeekFrom::Start(0)).ok()?;
+    let mut start = Vec::new();
+    (&mut file)
+        .take(MAX_SFX_SIZE)
+        .read_to_end(&mut start)
+        .ok()?;
+    match find_signature(&start) {
+        Some((offset, RarFormat::Rar50)) => dictionary5(file, offset as u64 + 8, len).ok()?,
+        _ => None,
+    }
+}
+
+/// walks the RAR5 blocks from `pos` up to `len` like [`encryption5`].
+fn dictionary5<R: Read + Seek>(mut file: R, mut pos: u64, len: u64) -> io::Result<Option<u64>> {
+    let mut largest = None;
+    while pos < len {
+        file.seek(SeekFrom::Start(pos))?;
+        file.read_exact(&mut [0; 4])?;
+        let (size, size_len) = vint(&mut file)?;
+        let mut header = Vec::new();
+        (&mut file).take(size).read_to_end(&mut header)?;
+        let mut fields = header.as_slice();
+        let (kind, _) = vint(&mut fields)?;
+        let (flags, _) = vint(&mut fields)?;
+        if flags & 0x1 != 0 {
+            vint(&mut fields)?;
+        }
+        let data = match flags & 0x2 {
+            0 => 0,
+            _ => vint(&mut fields)?.0,
+        };
+        match kind {
+            HEAD5_ENDARC => break,
+            HEAD5_CRYPT => return Ok(None),
+            HEAD5_FILE => {
+                let (file_flags, _) = vint(&mut fields)?;
+                // the unpacked size and the attributes
+                vint(&mut fields)?;
+                vint(&mut fields)?;
+                if file_flags & FHFL_UTIME != 0 {
+                    fields.read_exact(&mut [0; 4])?;
+                }
+                if file_flags & FHFL_CRC32 != 0 {
+                    fields.read_exact(&mut [0; 4])?;
+                }
+                let (info, _) = vint(&mut fields)?;
+                let size = match info & 0x3f {
+                    _ if file_flags & FHFL_DIRECTORY != 0 => None,
+                    0 => Some(0x20000 << ((info >> 10) & 0x0f)),
+                    // RAR 7 dictionaries may be larger and not a power of two
+                    1 => {
+                        let size = 0x20000u64 << ((info >> 10) & 0x1f);
+                        Some(size + size / 32 * ((info >> 15) & 0x1f))
+                    }
+                    _ => None,
+                };
+                largest = largest.max(size);
+            }
+            _ => {}
+        }
+        pos = [4, size_len, size, data]
+            .into_iter()
+            .try_fold(pos, u64::checked_add)
+            .filter(|&next| next <= len)
+            .ok_or(io::ErrorKind::InvalidData)?;
+    }
+    Ok(largest)
+}
+
+/// how the archive at `path` is encrypted: the encryption header of RAR5 archives with
+/// encrypted headers, or the encryption of the first encrypted entry, see
+/// `Archive::ReadHeader15` and `Archive::ProcessExtra50` in the UnRAR sources. `None` if
+/// the end of archive is reached first.
+///
+/// Fails with [`io::ErrorKind::Unsupported`] for RAR 1.4 archives and future formats, and
+/// with [`io::ErrorKind::InvalidData`] for sizes that cannot be right.
+pub(crate) fn encryption(path: &Path) -> io::Result<Option<EncryptionInfo>> {
+    encryption_of(io::BufReader::new(std::fs::File::open(path)?))
+}
+
+fn encryption_of<R: Read + Seek>(mut file: R) -> io::Result<Option<EncryptionInfo>> {
+    let len = file.seek(SeekFrom::End(0))?;
+    file.seek(SeekFrom::Start(0))?;
+    let mut start = Vec::new();
+    (&mut file).take(MAX_SFX_SIZE).read_to_end(&mut start)?;
+    match find_signature(&start) {
+        Some((offset, RarFormat::Rar15)) => encryption4(file, offset as u64 + 7, len),
+        Some((offset, RarFormat::Rar50)) => encryption5(file, offset as u64 + 8, len),
+        _ => Err(io::ErrorKind::Unsupported.into()),
+    }
+}
+
+/// walks the RAR5 blocks from `pos` up to `len`, failing with [`io::ErrorKind::InvalidData`]
+/// if a block claims to end beyond it.
+fn encryption5<R: Read + Seek>(
+    mut file: R,
+    mut pos: u64,
+    len: u64,
+) -> io::Result<Option<EncryptionInfo>> {
+    // the end of archive header is optional
+    while pos < len {
+        file.seek(SeekFrom::Start(pos))?;
+        file.read_exact(&mut [0; 4])?;
+        let (size, size_len) = vint(&mut file)?;
+        let mut header = Vec::new();
+        (&mut file).take(size).read_to_end(&mut header)?;
+        let mut fields = header.as_slice();
+        let (kind, _) = vint(&mut fields)?;
+        let (flags, _) = vint(&mut fields)?;
+        if kind == HEAD5_CRYPT {
+            // the encryption version, 0 for AES-256, and the flags precede the KDF count
+            vint(&mut fields)?;
+            vint(&mut fields)?;
+            return Ok(Some(aes256(&mut fields, true)?));
+        }
+        let extra = match flags & 0x1 {
+            0 => 0,
+            _ => vint(&mut fields)?.0,
+        };
+        let data = match flags & 0x2 {
+            0 => 0,
+            _ => vint(&mut fields)?.0,
+        };
+        match kind {
+            HEAD5_ENDARC => return Ok(None),
+            HEAD5_FILE => {
+                let start = header.len().saturating_sub(extra as usize);
+                let mut extra = &header[start..];
+                while !extra.is_empty() {
+                    let (size, _) = vint(&mut extra)?;
+                    let (mut record, rest) = extra.split_at(extra.len().min(size as usize));
+                    extra = rest;
+                    if vint(&mut record)?.0 == FHEXTRA_CRYPT {
+                        vint(&mut record)?;
+                        vint(&mut record)?;
+                        return Ok(Some(aes256(&mut record, false)?));
+                    }
+                }
+            }
+            _ => {}
+        }
+        pos = [4, size_len, size, data]
+            .into_iter()
+            .try_fold(pos, u64::checked_add)
+            .filter(|&next| next <= len)
+            .ok_or(io::ErrorKind::InvalidData)?;
+    }
+    Ok(None)
+}
+
+/// the RAR5 encryption with the binary logarithm of the KDF iterations read from `fields`.
+fn aes256(fields: &mut &[u8], encrypted_headers: bool) -> io::Result<EncryptionInfo> {
+    let mut lg2_count = [0];
+    fields.read_exact(&mut lg2_count)?;
+    Ok(EncryptionInfo {
+        method: EncryptionMethod::Aes256,
+        kdf_iterations: 1u32.checked_shl(u32::from(lg2_count[0])),
+        encrypted_headers,
+    })
+}
+
+/// walks the RAR4 blocks from `pos` up to `len`.
+fn encryption4<R: Read + Seek>(
+    mut file: R,
+    mut pos: u64,
+    len: u64,
+) -> io::Result<Option<EncryptionInfo>> {
+    while pos < len {
+        file.seek(SeekFrom::Start(pos))?;
+        // the base of the header along with the fields of file headers
+        let mut header = Vec::new();
+        (&mut file).take(7 + 25).read_to_end(&mut header)?;
+        if header.len() < 7 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let kind = header[2];
+        let flags = u16::from_le_bytes([header[3], header[4]]);
+        let method = match kind {
+            // only RAR 3 and later encrypt headers
+            HEAD3_MAIN if flags & MHD_PASSWORD != 0 => Some((EncryptionMethod::Aes128, true)),
+            // the version needed to unpack the entry tells the cipher
+            HEAD3_FILE if flags & LHD_PASSWORD != 0 && header.len() == 7 + 25 => {
+                let method = match header[7 + 17] {
+                    13 => EncryptionMethod::Rar13,
+                    15 => EncryptionMethod::Rar15,
+                    20 | 26 => EncryptionMethod::Rar20,
+                    _ => EncryptionMethod::Aes128,
+                };
+                Some((method, false))
+            }
+            _ => None,
+        };
+        if let Some((method, encrypted_headers)) = method {
+            return Ok(Some(EncryptionInfo {
+                method,
+                kdf_iterations: None,
+                encrypted_headers,
+            }));
+        }
+        file.seek(SeekFrom::Start(pos))?;
+        match block4(&mut file, pos)? {
+            Block::Next(next) if next > pos => pos = next,
+            _ => return Ok(None),
+        }
+    }
+    Ok(None)
+}
+
+fn ends_early<R: Read + Seek>(mut file: R) -> io::Result<bool> {
+    let len = file.seek(SeekFrom::End(0))?;
+    file.seek(SeekFrom::Start(0))?;
+    let mut start = Vec::new();
+    (&mut file).take(MAX_SFX_SIZE).read_to_end(&mut start)?;
+    let (walk, offset, signature): (fn(&mut R, u64) -> io::Result<Block>, _, _) =
+        match find_signature(&start) {
+            Some((offset, RarFormat::Rar15)) => (block4, offset, 7),
+            Some((offset, RarFormat::Rar50)) => (block5, offset, 8),
+            _ => return Ok(false),
+        };
+    let mut pos = (offset + signature) as u64;
+    loop {
+        if pos == len {
+            return Ok(false);
+        }
+        file.seek(SeekFrom::Start(pos))?;
+        match walk(&mut file, pos) {
+            Ok(Block::Next(next)) if next > pos => pos = next,
+            Ok(Block::Next(_)) | Ok(Block::Unknown) | Ok(Block::End) => return Ok(false),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(true),
+            Err(e) => return Err(e),
+        }
+        if pos > len {
+            return Ok(true);
+        }
+    }
+}
+
+enum Block {
+    /// the position of the next block
+    Next(u64),
+    /// the end of archive header
+    End,
+    /// the headers cannot be read further
+    Unknown,
+}
+
+/// reads the RAR5 block at `pos`.
+fn block5<R: Read>(file: &mut R, pos: u64) -> io::Result<Block> {
+    let mut crc = [0; 4];
+    file.read_exact(&mut crc)?;
+    let (size, size_len) = vint(file)?;
+    let mut header = file.take(size);
+    let (kind, _) = vint(&mut header)?;
+    let (flags, _) = vint(&mut header)?;
+    if flags & 0x1 != 0 {
+        vint(&mut header)?;
+    }
+    let data = match flags & 0x2 {
+        0 => 0,
+        _ => vint(&mut header)?.0,
+    };
+    Ok(match kind {
+        HEAD5_ENDARC => Block::End,
+        HEAD5_CRYPT => Block::Unknown,
+        _ => [4, size_len, size, data]
+            .into_iter()
+            .try_fold(pos, u64::checked_add)
+            .map_or(Block::Unknown, Block::Next),
+    })
+}
+
+/// reads a RAR5 variable length integer, returning it along with its length.
+fn vint<R: Read>(file: &mut R) -> io::Result<(u64, u64)> {
+    let mut value = 0;
+    for i in 0..10 {
+        let mut byte = [0];
+        file.read_exact(&mut byte)?;
+        value |= u64::from(byte[0] & 0x7f) << (7 * i);
+        if byte[0] & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+    Err(io::ErrorKind::InvalidData.into())
+}
+
+/// reads the RAR4 block at `pos`.
+fn block4<R: Read>(file: &mut R, pos: u64) -> io::Result<Block> {
+    let mut header = [0; 7];
+    file.read_exact(&mut header)?;
+    let kind = header[2];
+    let flags = u16::from_le_bytes([header[3], header[4]]);
+    let size = u64::from(u16::from_le_bytes([header[5], header[6]]));
+    if size < 7 {
+        return Ok(Block::Unknown);
+    }
+    let data = match kind {
+        HEAD3_FILE | HEAD3_SERVICE => {
+            let mut fields = [0; 25];
+            file.read_exact(&mut fields)?;
+            let low = u32::from_le_bytes(fields[..4].try_into().unwrap());
+            let high = match flags & LHD_LARGE {
+                0 => 0,
+                _ => {
+                    let mut high = [0; 4];
+                    file.read_exact(&mut high)?;
+                    u32::from_le_bytes(high)
+                }
+            };
+            u64::from(high) << 32 | u64::from(low)
+        }
+        _ if flags & LONG_BLOCK != 0 => {
+            let mut data = [0; 4];
+            file.read_exact(&mut data)?;
+            u64::from(u32::from_le_bytes(data))
+        }
+        _ => 0,
+    };
+    Ok(match kind {
+        HEAD3_ENDARC => Block::End,
+        HEAD3_MAIN if flags & MHD_PASSWORD != 0 => Block::Unknown,
+        _ => (pos.checked_add(size))
+            .and_then(|next| next.checked_add(data))
+            .map_or(Block::Unknown, Block::Next),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ends_early, locates_quick_open};
+    use std::io::Cursor;
+
+    fn truncated(data: &[u8]) -> bool {
+        ends_early(Cursor::new(data)).unwrap()
+    }
+
+    #[test]
+    fn rar5() {
+        let archive = std::fs::read("data/directory.rar").unwrap();
+        assert!(!truncated(&archive));
+        // the end of archive header is optional
+        assert!(!truncated(&archive[..archive.len() - 8]));
+        assert!(truncated(&archive[..archive.len() - 12]));
+        assert!(truncated(&archive[..10]));
+    }
+
+    #[test]
+    fn rar4() {
+        let archive = std::fs::read("data/version.rar").unwrap();
+        assert!(!truncated(&archive));
+        assert!(truncated(&archive[..archive.len() - 10]));
+        assert!(truncated(&archive[..30]));
+    }
+
+    #[test]
+    fn not_rar() {
+        assert!(!truncated(b"not an archive"));
+        assert!(!truncated(b""));
+    }
+
+    #[test]
+    fn huge_data_size() {
+        // a file header with u64::MAX bytes of data must not overflow the position
+        let archive = std::fs::read("data/huge-data-size.rar").unwrap();
+        assert!(!truncated(&archive));
+        let err = super::encryption_of(Cursor::new(&archive)).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        let mut rar4 = std::fs::read("data/version.rar").unwrap();
+        // the low and high data size of the file header, with LHD_LARGE set
+        let file = 7 + 13;
+        rar4[file + 4] |= 0x1;
+        rar4[file + 7..file + 11].fill(0xff);
+        rar4[file + 7 + 25..file + 7 + 29].fill(0xff);
+        assert!(!truncated(&rar4));
+    }
+
+    #[test]
+    fn quick_open() {
+        let archive = std::fs::read("data/quickopen.rar").unwrap();
+        assert!(locates_quick_open(Cursor::new(&archive)).unwrap());
+        // the main header is cut
+        assert!(locates_quick_open(Cursor::new(&archive[..12])).is_err());
+        let archive = std::fs::read("data/version.rar").unwrap();
+        assert!(!locates_quick_open(Cursor::new(&archive)).unwrap());
+    }
+
+    #[test]
+    fn dictionary_size() {
+        use std::path::Path;
+        let size = |path: &str| super::dictionary_size(Path::new(path));
+        assert_eq!(size("data/solid.rar"), Some(1 << 20));
+        // the directory before the file has no dictionary
+        assert_eq!(size("data/directory.rar"), Some(128 << 10));
+        // the headers are encrypted, or older archives store none
+        assert_eq!(size("data/comment-hpw-password.rar"), None);
+        assert_eq!(size("data/version.rar"), None);
+        assert_eq!(size("data/huge-data-size.rar"), None);
+    }
+}
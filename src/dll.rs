@@ -0,0 +1,49 @@
+use super::error::*;
+use super::*;
+use std::sync::OnceLock;
+
+/// Returns the API version of the underlying UnRAR library, as reported by
+/// [`RARGetDllVersion`](native::RARGetDllVersion).
+///
+/// This does not open any archive. The value is queried once and cached afterwards.
+///
+/// # Example
+///
+/// ```
+/// assert!(unrar::dll_version() >= 9);
+/// ```
+pub fn dll_version() -> i32 {
+    static INSTANCE: OnceLock<i32> = OnceLock::new();
+    *INSTANCE.get_or_init(|| unsafe { native::RARGetDllVersion() })
+}
+
+/// the API version of UnRAR 5.0, the first to read RAR5 archives.
+const RAR5_DLL_VERSION: i32 = 6;
+
+/// Tells whether the underlying UnRAR library reads RAR5 archives, i.e. whether its
+/// [API version](dll_version) is at least 6 (UnRAR 5.0).
+///
+/// The bundled library always does, so this only matters when linking an older library
+/// installed on the system without the `static` feature. Older versions leave the header
+/// fields added for RAR5 empty, so [`FileHeader::blake2sp`](crate::FileHeader::blake2sp),
+/// [`FileHeader::redirect_type`](crate::FileHeader::redirect_type) and the high resolution
+/// times are `None` with them. Methods that cannot work without these fields, like
+/// [`OpenArchive::extract_and_verify`](crate::OpenArchive::extract_and_verify), fail with
+/// [`Code::Unsupported`] instead.
+///
+/// # Example
+///
+/// ```
+/// assert!(unrar::supports_rar5());
+/// ```
+pub fn supports_rar5() -> bool {
+    dll_version() >= RAR5_DLL_VERSION
+}
+
+/// fails for methods relying on the header fields added for RAR5, see [`supports_rar5`].
+pub(crate) fn require_rar5(when: When) -> UnrarResult<()> {
+    match supports_rar5() {
+        true => Ok(()),
+        false => Err(UnrarError::from(Code::Unsupported, when)),
+    }
+}
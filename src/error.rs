@@ -4,11 +4,37 @@ use super::*;
 use std::error;
 use std::ffi;
 use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::result::Result;
 
 
+/// raw value of [`Code::ENul`], outside of the range used by the UnRAR library.
+const ENUL: i32 = 0x10000;
+/// raw value of [`Code::Cancelled`], outside of the range used by the UnRAR library.
+const CANCELLED: i32 = 0x10001;
+/// raw value of [`Code::Unverifiable`], outside of the range used by the UnRAR library.
+const UNVERIFIABLE: i32 = 0x10002;
+/// raw value of [`Code::UnsafePath`], outside of the range used by the UnRAR library.
+const UNSAFE_PATH: i32 = 0x10003;
+/// raw value of [`Code::SizeLimitExceeded`], outside of the range used by the UnRAR library.
+const SIZE_LIMIT_EXCEEDED: i32 = 0x10004;
+/// raw value of [`Code::EntryNotFound`], outside of the range used by the UnRAR library.
+const ENTRY_NOT_FOUND: i32 = 0x10005;
+/// raw value of [`Code::Unsupported`], outside of the range used by the UnRAR library.
+const UNSUPPORTED: i32 = 0x10006;
+/// raw value of [`Code::Truncated`], outside of the range used by the UnRAR library.
+const TRUNCATED: i32 = 0x10007;
+
+/// The code of an error, most of them returned by the UnRAR library.
+///
+/// With the `serde` feature, it serializes as the name of the variant, e.g. `"BadArchive"`,
+/// and `{"Other": 42}` for codes this crate does not know about. Variants keep their names
+/// across minor versions, so serialized codes stay readable.
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
+#[non_exhaustive]
 pub enum Code {
     Success = native::ERAR_SUCCESS,
     EndArchive = native::ERAR_END_ARCHIVE,
@@ -32,47 +58,144 @@ pub enum Code {
     // record without its source file.
     EReference = native::ERAR_EREFERENCE,
     BadPassword = native::ERAR_BAD_PASSWORD,
+    // Not returned by the UnRAR library, values from 0x10000 on are never used by it.
+    // A path passed to the library contains a nul value.
+    ENul = ENUL,
+    // The operation was aborted by a cancellation token.
+    Cancelled = CANCELLED,
+    // The entry's data cannot be verified, as it stores no hash this crate can compute.
+    Unverifiable = UNVERIFIABLE,
+    // The entry's path would escape the directory it is extracted into.
+    UnsafePath = UNSAFE_PATH,
+    // The unpacked data exceeds the configured size limit.
+    SizeLimitExceeded = SIZE_LIMIT_EXCEEDED,
+    // The archive contains no entry with the requested name.
+    EntryNotFound = ENTRY_NOT_FOUND,
+    // The UnRAR library is too old for the operation.
+    Unsupported = UNSUPPORTED,
+    // The archive ends before its end of archive header, e.g. an interrupted download.
+    Truncated = TRUNCATED,
+    // A code this crate does not know about, e.g. from a newer UnRAR library.
+    Other(i32),
 }
 
+/// The operation an error occurred in.
+///
+/// With the `serde` feature, it serializes as the name of the variant, e.g. `"Open"`.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum When {
     Open,
     Read,
     Process,
 }
 
+/// A coarse classification of errors for grouping them, see [`UnrarError::category`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ErrorCategory {
+    /// the archive is damaged or incomplete
+    Corruption,
+    /// the password is missing or wrong
+    Password,
+    /// reading or writing a file failed
+    Io,
+    /// the archive or the operation needs something this crate or UnRAR library lacks
+    Unsupported,
+    /// the operation was cancelled by the caller
+    UserAbort,
+    /// any other error, e.g. an unsafe path or an exceeded limit
+    Other,
+}
+
 impl Code {
-    pub fn from(code: i32) -> Option<Self> {
+    pub fn from(code: i32) -> Self {
         use Code::*;
         match code {
-            native::ERAR_SUCCESS => Some(Success),
-            native::ERAR_END_ARCHIVE => Some(EndArchive),
-            native::ERAR_NO_MEMORY => Some(NoMemory),
-            native::ERAR_BAD_DATA => Some(BadData),
-            native::ERAR_BAD_ARCHIVE => Some(BadArchive),
-            native::ERAR_UNKNOWN_FORMAT => Some(UnknownFormat),
-            native::ERAR_EOPEN => Some(EOpen),
-            native::ERAR_ECREATE => Some(ECreate),
-            native::ERAR_ECLOSE => Some(EClose),
-            native::ERAR_EREAD => Some(ERead),
-            native::ERAR_EWRITE => Some(EWrite),
-            native::ERAR_SMALL_BUF => Some(SmallBuf),
-            native::ERAR_UNKNOWN => Some(Unknown),
-            native::ERAR_MISSING_PASSWORD => Some(MissingPassword),
-            native::ERAR_EREFERENCE => Some(EReference),
-            native::ERAR_BAD_PASSWORD => Some(BadPassword),
-            _ => None,
+            native::ERAR_SUCCESS => Success,
+            native::ERAR_END_ARCHIVE => EndArchive,
+            native::ERAR_NO_MEMORY => NoMemory,
+            native::ERAR_BAD_DATA => BadData,
+            native::ERAR_BAD_ARCHIVE => BadArchive,
+            native::ERAR_UNKNOWN_FORMAT => UnknownFormat,
+            native::ERAR_EOPEN => EOpen,
+            native::ERAR_ECREATE => ECreate,
+            native::ERAR_ECLOSE => EClose,
+            native::ERAR_EREAD => ERead,
+            native::ERAR_EWRITE => EWrite,
+            native::ERAR_SMALL_BUF => SmallBuf,
+            native::ERAR_UNKNOWN => Unknown,
+            native::ERAR_MISSING_PASSWORD => MissingPassword,
+            native::ERAR_EREFERENCE => EReference,
+            native::ERAR_BAD_PASSWORD => BadPassword,
+            _ => Other(code),
+        }
+    }
+
+    /// Returns the integer code as returned by the UnRAR library, or the code
+    /// reserved for it if it does not originate from the library (e.g. [`Code::Cancelled`]).
+    ///
+    /// Except for these, `Code::from(code.as_raw()) == code`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::error::Code;
+    /// assert_eq!(Code::from(Code::BadPassword.as_raw()), Code::BadPassword);
+    /// assert_eq!(Code::Other(1000).as_raw(), 1000);
+    /// ```
+    pub fn as_raw(&self) -> i32 {
+        use Code::*;
+        match *self {
+            Success => native::ERAR_SUCCESS,
+            EndArchive => native::ERAR_END_ARCHIVE,
+            NoMemory => native::ERAR_NO_MEMORY,
+            BadData => native::ERAR_BAD_DATA,
+            BadArchive => native::ERAR_BAD_ARCHIVE,
+            UnknownFormat => native::ERAR_UNKNOWN_FORMAT,
+            EOpen => native::ERAR_EOPEN,
+            ECreate => native::ERAR_ECREATE,
+            EClose => native::ERAR_ECLOSE,
+            ERead => native::ERAR_EREAD,
+            EWrite => native::ERAR_EWRITE,
+            SmallBuf => native::ERAR_SMALL_BUF,
+            Unknown => native::ERAR_UNKNOWN,
+            MissingPassword => native::ERAR_MISSING_PASSWORD,
+            EReference => native::ERAR_EREFERENCE,
+            BadPassword => native::ERAR_BAD_PASSWORD,
+            ENul => ENUL,
+            Cancelled => CANCELLED,
+            Unverifiable => UNVERIFIABLE,
+            UnsafePath => UNSAFE_PATH,
+            SizeLimitExceeded => SIZE_LIMIT_EXCEEDED,
+            EntryNotFound => ENTRY_NOT_FOUND,
+            Unsupported => UNSUPPORTED,
+            Truncated => TRUNCATED,
+            Other(code) => code,
         }
     }
 }
 
-#[derive(PartialEq)]
 pub struct UnrarError {
     pub code: Code,
     pub when: When,
+    /// The entry being processed when the error occurred, if known.
+    pub file: Option<PathBuf>,
+    source: Option<Box<dyn error::Error + Send + Sync>>,
+    partial: Option<Vec<u8>>,
+}
+
+impl std::error::Error for UnrarError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.source.as_deref().map(|e| e as _)
+    }
 }
 
-impl std::error::Error for UnrarError {}
+impl PartialEq for UnrarError {
+    /// compares everything but the source, which is not comparable
+    fn eq(&self, other: &Self) -> bool {
+        self.code == other.code && self.when == other.when && self.file == other.file
+    }
+}
 
 impl fmt::Debug for UnrarError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -103,16 +226,121 @@ impl fmt::Display for UnrarError {
             (MissingPassword, _) => write!(f, "Password for encrypted archive not specified"),
             (EReference, _) => write!(f, "Cannot open file source for reference record"),
             (BadPassword, _) => write!(f, "Wrong password was specified"),
+            (ENul, _) => write!(f, "Path contains a nul value"),
+            (Cancelled, _) => write!(f, "Operation cancelled by callback"),
+            (Unverifiable, _) => write!(f, "Entry has no hash to verify its data against"),
+            (UnsafePath, _) => write!(f, "Entry path escapes the destination directory"),
+            (SizeLimitExceeded, _) => write!(f, "Unpacked data exceeds the size limit"),
+            (EntryNotFound, _) => write!(f, "No such entry in the archive"),
+            (Unsupported, _) => write!(f, "Operation not supported by this UnRAR version"),
+            (Truncated, _) => write!(f, "Archive is truncated"),
+            (Other(code), _) => write!(f, "Unknown error code ({code})"),
             (Unknown, _) => write!(f, "Unknown error"),
             (EndArchive, _) => write!(f, "Archive end"),
             (Success, _) => write!(f, "Success"),
+        }?;
+        if let Some(file) = &self.file {
+            write!(f, " (while processing {})", file.display())?;
         }
+        Ok(())
     }
 }
 
 impl UnrarError {
     pub fn from(code: Code, when: When) -> Self {
-        UnrarError { code, when }
+        UnrarError {
+            code,
+            when,
+            file: None,
+            source: None,
+            partial: None,
+        }
+    }
+
+    /// Classifies the error by its code and when it occurred, e.g. to show consistent
+    /// messages or to decide whether to retry with another password.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::error::{Code, ErrorCategory, UnrarError, When};
+    /// let err = UnrarError::from(Code::BadPassword, When::Process);
+    /// assert_eq!(err.category(), ErrorCategory::Password);
+    /// let err = UnrarError::from(Code::EOpen, When::Open);
+    /// assert_eq!(err.category(), ErrorCategory::Io);
+    /// ```
+    pub fn category(&self) -> ErrorCategory {
+        use self::Code::*;
+        match (self.code, self.when) {
+            // reported for an unknown encryption method when opening
+            (UnknownFormat, When::Open) => ErrorCategory::Unsupported,
+            (BadData | BadArchive | UnknownFormat | Truncated, _) => ErrorCategory::Corruption,
+            (MissingPassword | BadPassword, _) => ErrorCategory::Password,
+            (EOpen | ECreate | EClose | ERead | EWrite, _) => ErrorCategory::Io,
+            (Unsupported | Unverifiable, _) => ErrorCategory::Unsupported,
+            (Cancelled, _) => ErrorCategory::UserAbort,
+            _ => ErrorCategory::Other,
+        }
+    }
+
+    /// The data read from a corrupted entry before the error, if
+    /// [kept](crate::Archive::with_allow_partial).
+    pub fn partial_data(&self) -> Option<&[u8]> {
+        self.partial.as_deref()
+    }
+
+    /// Takes the data read from a corrupted entry before the error, if
+    /// [kept](crate::Archive::with_allow_partial).
+    pub fn into_partial_data(self) -> Option<Vec<u8>> {
+        self.partial
+    }
+
+    /// attaches the data read before the error.
+    pub(crate) fn with_partial_data(mut self, data: Vec<u8>) -> Self {
+        self.partial = Some(data);
+        self
+    }
+
+    /// attaches the underlying cause of the error, returned from [`source`](error::Error::source).
+    pub(crate) fn with_source<E>(mut self, source: E) -> Self
+    where
+        E: Into<Box<dyn error::Error + Send + Sync>>,
+    {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// attaches the entry being processed to the error.
+    pub(crate) fn with_file(mut self, file: &Path) -> Self {
+        self.file = Some(file.to_path_buf());
+        self
+    }
+}
+
+impl From<UnrarError> for io::Error {
+    /// Takes the [`io::ErrorKind`] of the underlying [`io::Error`] if there is one, and
+    /// otherwise maps the error [`Code`] to the closest kind,
+    /// keeping the original `UnrarError` as the inner error.
+    fn from(e: UnrarError) -> io::Error {
+        use self::Code::*;
+        let source = e.source.as_deref().and_then(|s| s.downcast_ref::<io::Error>());
+        if let Some(kind) = source.map(io::Error::kind) {
+            return io::Error::new(kind, e);
+        }
+        let kind = match e.code {
+            EOpen | EntryNotFound => io::ErrorKind::NotFound,
+            ECreate => io::ErrorKind::PermissionDenied,
+            Truncated => io::ErrorKind::UnexpectedEof,
+            EWrite => io::ErrorKind::WriteZero,
+            NoMemory => io::ErrorKind::OutOfMemory,
+            BadData | BadArchive | UnknownFormat | UnsafePath | SizeLimitExceeded => {
+                io::ErrorKind::InvalidData
+            }
+            MissingPassword | BadPassword | ENul => io::ErrorKind::InvalidInput,
+            Unverifiable | Unsupported => io::ErrorKind::Unsupported,
+            _ => io::ErrorKind::Other,
+        };
+        io::Error::new(kind, e)
     }
 }
 
@@ -133,6 +361,12 @@ impl error::Error for NulError {
     }
 }
 
+impl From<NulError> for UnrarError {
+    fn from(e: NulError) -> UnrarError {
+        UnrarError::from(Code::ENul, When::Open).with_source(e)
+    }
+}
+
 impl<C> From<widestring::error::ContainsNul<C>> for NulError {
     fn from(e: widestring::error::ContainsNul<C>) -> NulError {
         NulError(e.nul_position())
@@ -144,3 +378,80 @@ impl From<ffi::NulError> for NulError {
         NulError(e.nul_position())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{native, Code};
+
+    #[test]
+    fn from_never_cancelled() {
+        assert_eq!(Code::from(0x10001), Code::Other(0x10001));
+        assert!((-1..=0x20000).all(|code| Code::from(code) != Code::Cancelled));
+        assert!((-1..=0x20000).all(|code| Code::from(code) != Code::Unverifiable));
+        assert!((-1..=0x20000).all(|code| Code::from(code) != Code::UnsafePath));
+        assert!((-1..=0x20000).all(|code| Code::from(code) != Code::SizeLimitExceeded));
+        assert!((-1..=0x20000).all(|code| Code::from(code) != Code::EntryNotFound));
+        assert!((-1..=0x20000).all(|code| Code::from(code) != Code::Unsupported));
+        assert!((-1..=0x20000).all(|code| Code::from(code) != Code::Truncated));
+    }
+
+    #[test]
+    fn as_raw_roundtrip() {
+        assert!((-1..=0x20000)
+            .filter(|code| {
+                ![
+                    super::ENUL,
+                    super::CANCELLED,
+                    super::UNVERIFIABLE,
+                    super::UNSAFE_PATH,
+                    super::SIZE_LIMIT_EXCEEDED,
+                    super::ENTRY_NOT_FOUND,
+                    super::UNSUPPORTED,
+                    super::TRUNCATED,
+                ]
+                .contains(code)
+            })
+            .all(|code| Code::from(code).as_raw() == code));
+        assert_eq!(Code::ENul.as_raw(), super::ENUL);
+        assert_eq!(Code::Cancelled.as_raw(), super::CANCELLED);
+        assert_eq!(Code::Unverifiable.as_raw(), super::UNVERIFIABLE);
+        assert_eq!(Code::UnsafePath.as_raw(), super::UNSAFE_PATH);
+        assert_eq!(Code::SizeLimitExceeded.as_raw(), super::SIZE_LIMIT_EXCEEDED);
+        assert_eq!(Code::EntryNotFound.as_raw(), super::ENTRY_NOT_FOUND);
+        assert_eq!(Code::Unsupported.as_raw(), super::UNSUPPORTED);
+        assert_eq!(Code::Truncated.as_raw(), super::TRUNCATED);
+    }
+
+    #[test]
+    fn from_unknown_code() {
+        assert_eq!(Code::from(native::ERAR_BAD_PASSWORD), Code::BadPassword);
+        assert_eq!(Code::from(1000), Code::Other(1000));
+        let err = super::UnrarError::from(Code::Other(1000), super::When::Process);
+        assert_eq!(err.to_string(), "Unknown error code (1000)");
+    }
+
+    #[test]
+    fn category() {
+        use super::{ErrorCategory, UnrarError, When};
+        let category = |code, when| UnrarError::from(code, when).category();
+        assert_eq!(category(Code::BadData, When::Process), ErrorCategory::Corruption);
+        assert_eq!(category(Code::BadArchive, When::Open), ErrorCategory::Corruption);
+        assert_eq!(category(Code::Truncated, When::Read), ErrorCategory::Corruption);
+        assert_eq!(category(Code::UnknownFormat, When::Read), ErrorCategory::Corruption);
+        assert_eq!(category(Code::UnknownFormat, When::Open), ErrorCategory::Unsupported);
+        assert_eq!(category(Code::MissingPassword, When::Open), ErrorCategory::Password);
+        assert_eq!(category(Code::EWrite, When::Process), ErrorCategory::Io);
+        assert_eq!(category(Code::Unsupported, When::Open), ErrorCategory::Unsupported);
+        assert_eq!(category(Code::Cancelled, When::Process), ErrorCategory::UserAbort);
+        assert_eq!(category(Code::UnsafePath, When::Process), ErrorCategory::Other);
+        assert_eq!(category(Code::Other(1000), When::Read), ErrorCategory::Other);
+    }
+
+    #[test]
+    fn unsupported() {
+        let err = super::UnrarError::from(Code::Unsupported, super::When::Open);
+        assert_eq!(err.to_string(), "Operation not supported by this UnRAR version");
+        let err = std::io::Error::from(err);
+        assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+    }
+}
@@ -0,0 +1,40 @@
+//! Reading the entries of an archive one at a time through an [`Iterator`].
+
+use crate::error::UnrarResult;
+use crate::open_archive::{CursorBeforeHeader, FileHeader, OpenArchive, Process};
+use std::iter::FusedIterator;
+
+/// Yields each entry along with its data, unpacking an entry only when it is pulled,
+/// see [`OpenArchive::into_extracting_iter`].
+///
+/// An entry that fails to unpack is yielded as an `Err`. Iteration goes on with the next
+/// entry after a [corrupted](crate::error::Code::BadData) one, which the UnRAR library gets
+/// past, and ends after any other error or at the end of the archive.
+#[derive(Debug)]
+pub struct ExtractingIter {
+    archive: Option<OpenArchive<Process, CursorBeforeHeader>>,
+}
+
+impl ExtractingIter {
+    pub(crate) fn new(archive: OpenArchive<Process, CursorBeforeHeader>) -> Self {
+        ExtractingIter {
+            archive: Some(archive),
+        }
+    }
+}
+
+impl Iterator for ExtractingIter {
+    type Item = UnrarResult<(FileHeader, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let header = match self.archive.take()?.read_header() {
+            Ok(header) => header?,
+            Err(e) => return Some(Err(e)),
+        };
+        let (entry, result, rest) = header.read_continuing();
+        self.archive = rest;
+        Some(result.map(|data| (entry, data)))
+    }
+}
+
+impl FusedIterator for ExtractingIter {}
@@ -0,0 +1,168 @@
+//! Checksums to verify unpacked data against the hash stored in the archive.
+
+/// The hash of the unpacked data stored for an entry, see [`FileHeader::stored_hash`].
+///
+/// [`FileHeader::stored_hash`]: crate::FileHeader::stored_hash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StoredHash {
+    /// the entry stores no hash, e.g. directories and symbolic links
+    None,
+    /// CRC32, stored by RAR4 archives and by default by RAR5 archives
+    Crc32(u32),
+    /// BLAKE2sp-256, stored by RAR5 archives created with `-htb`
+    Blake2sp([u8; 32]),
+}
+
+impl StoredHash {
+    /// the kind of the hash, without the digest.
+    pub fn kind(&self) -> HashKind {
+        match self {
+            StoredHash::None => HashKind::None,
+            StoredHash::Crc32(_) => HashKind::Crc32,
+            StoredHash::Blake2sp(_) => HashKind::Blake2sp,
+        }
+    }
+}
+
+/// The kind of hash stored for an entry, see [`FileHeader::hash_kind`].
+///
+/// [`FileHeader::hash_kind`]: crate::FileHeader::hash_kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HashKind {
+    /// no hash, the data cannot be verified
+    None,
+    /// CRC32
+    Crc32,
+    /// BLAKE2sp-256
+    Blake2sp,
+}
+
+/// computes the same kind of hash as stored for an entry.
+pub(crate) enum Hasher {
+    Crc32(Crc32),
+    #[cfg(feature = "blake2")]
+    Blake2sp(Box<Blake2sp>),
+}
+
+impl Hasher {
+    /// a hasher for the kind of `hash`, or `None` if it cannot be computed.
+    pub(crate) fn new(hash: &StoredHash) -> Option<Self> {
+        match hash {
+            StoredHash::Crc32(_) => Some(Hasher::Crc32(Crc32::default())),
+            #[cfg(feature = "blake2")]
+            StoredHash::Blake2sp(_) => Some(Hasher::Blake2sp(Box::default())),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Crc32(crc) => crc.update(data),
+            #[cfg(feature = "blake2")]
+            Hasher::Blake2sp(blake2sp) => blake2sp.update(data),
+        }
+    }
+
+    pub(crate) fn finish(self) -> StoredHash {
+        match self {
+            Hasher::Crc32(crc) => StoredHash::Crc32(crc.finish()),
+            #[cfg(feature = "blake2")]
+            Hasher::Blake2sp(blake2sp) => StoredHash::Blake2sp(blake2sp.finish()),
+        }
+    }
+}
+
+/// lookup table for the reflected CRC-32 polynomial 0xEDB88320 used by RAR.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// running CRC-32 over chunks of unpacked data.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Crc32(u32);
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Crc32(0xFFFF_FFFF)
+    }
+}
+
+impl Crc32 {
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.0 = CRC32_TABLE[((self.0 ^ byte as u32) & 0xFF) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u32 {
+        !self.0
+    }
+}
+
+/// running BLAKE2sp-256 over chunks of unpacked data, as used by RAR5.
+#[cfg(feature = "blake2")]
+#[derive(Default)]
+pub(crate) struct Blake2sp(blake2s_simd::blake2sp::State);
+
+#[cfg(feature = "blake2")]
+impl Blake2sp {
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    pub(crate) fn finish(self) -> [u8; 32] {
+        *self.0.finalize().as_array()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Crc32;
+
+    #[test]
+    fn crc32_chunks() {
+        let mut crc = Crc32::default();
+        assert_eq!(crc.finish(), 0);
+        crc.update(b"1234");
+        crc.update(b"56789");
+        assert_eq!(crc.finish(), 0xCBF4_3926);
+    }
+
+    #[cfg(feature = "blake2")]
+    #[test]
+    fn blake2sp() {
+        use super::Blake2sp;
+        let hex = |digest: [u8; 32]| digest.map(|b| format!("{b:02x}")).concat();
+        // from the BLAKE2 reference test vectors
+        assert_eq!(
+            hex(Blake2sp::default().finish()),
+            "dd0e891776933f43c7d032b08a917e25741f8aa9a12c12e1cac8801500f2ca4f"
+        );
+        let data: Vec<u8> = (0..2000).map(|i| (i % 251) as u8).collect();
+        let mut chunked = Blake2sp::default();
+        for chunk in data.chunks(77) {
+            chunked.update(chunk);
+        }
+        assert_eq!(
+            hex(chunked.finish()),
+            "b8a9bf7720ab2eaccce7c2283f50a489171682e17c82550127ae671d5c3b2d92"
+        );
+    }
+}
@@ -1,14 +1,39 @@
 #![doc = include_str!("../README.md")]
 #![warn(missing_docs)]
 
-pub use archive::Archive;
+pub use archive::{Archive, ArchiveBuilder, ArchiveSummary, TestReport, VolumePaths};
+#[cfg(feature = "tokio")]
+pub use archive::extract_file_async;
 use unrar_sys as native;
+
+/// emits a `tracing` event at the given level with the `tracing` feature, compiling to nothing
+/// without it, so its fields are not even evaluated.
+macro_rules! ffi_event {
+    ($level:ident, $($field:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::event!(tracing::Level::$level, $($field)*);
+    };
+}
+
 mod archive;
+mod blocks;
+mod dll;
+mod hash;
 pub mod error;
+mod extracting;
+mod memory;
 mod pathed;
+mod pattern;
+mod reader;
 mod open_archive;
+pub use dll::{dll_version, supports_rar5};
 pub use error::UnrarResult;
+pub use extracting::ExtractingIter;
+pub use hash::{HashKind, StoredHash};
+pub use reader::EntryReader;
 pub use open_archive::{
-    CursorBeforeFile, CursorBeforeHeader, FileHeader, List, ListSplit, OpenArchive, Process,
-    VolumeInfo,
+    detect_format, AuthenticityInfo, CompressionMethod, CursorBeforeFile, CursorBeforeHeader,
+    EncryptionInfo, EncryptionMethod, EntryAction, EntryFlags, ExtractOutcome, FileHeader, HostOs,
+    List, ListSplit, OpenArchive, OverwritePolicy, Process, RarFormat, RedirectType, VolumeInfo,
+    VolumeNaming,
 };
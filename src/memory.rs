@@ -0,0 +1,97 @@
+use std::io;
+use std::path::PathBuf;
+
+/// An anonymous in-memory file backing an archive opened via
+/// [`Archive::from_bytes`](crate::Archive::from_bytes).
+///
+/// The UnRAR library can only open archives by path, so the data is exposed
+/// to it via `/proc/self/fd/N`. The file must outlive the archive handle.
+///
+/// The buffer the data was copied from is kept to hand it back to the caller, see
+/// [`OpenArchive::into_bytes`](crate::OpenArchive::into_bytes).
+#[derive(Debug)]
+pub(crate) struct MemoryFile {
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    file: std::fs::File,
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    data: Vec<u8>,
+}
+
+impl MemoryFile {
+    #[cfg(target_os = "linux")]
+    pub(crate) fn new(data: Vec<u8>) -> io::Result<Self> {
+        use std::io::Write;
+        use std::os::fd::FromRawFd;
+
+        let fd = unsafe { libc::memfd_create(c"unrar".as_ptr(), libc::MFD_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut file = unsafe { std::fs::File::from_raw_fd(fd) };
+        file.write_all(&data)?;
+        Ok(MemoryFile { file, data })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub(crate) fn new(_data: Vec<u8>) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "in-memory archives are only supported on Linux",
+        ))
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn path(&self) -> PathBuf {
+        use std::os::fd::AsRawFd;
+
+        PathBuf::from(format!("/proc/self/fd/{}", self.file.as_raw_fd()))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub(crate) fn path(&self) -> PathBuf {
+        unreachable!("MemoryFile cannot be constructed on this platform")
+    }
+
+    /// closes the file, returning the buffer the data was copied from.
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+/// the path the UnRAR library can open the file descriptor `fd` of this process by,
+/// see [`Archive::from_fd`](crate::Archive::from_fd).
+#[cfg(unix)]
+pub(crate) fn fd_path(fd: std::os::fd::RawFd) -> PathBuf {
+    match cfg!(target_os = "linux") {
+        true => PathBuf::from(format!("/proc/self/fd/{fd}")),
+        false => PathBuf::from(format!("/dev/fd/{fd}")),
+    }
+}
+
+/// the path of the file `handle` refers to, see
+/// [`Archive::from_handle`](crate::Archive::from_handle).
+#[cfg(windows)]
+pub(crate) fn handle_path(handle: std::os::windows::io::RawHandle) -> io::Result<PathBuf> {
+    use std::os::windows::ffi::OsStringExt;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetFinalPathNameByHandleW(
+            file: std::os::windows::io::RawHandle,
+            path: *mut u16,
+            len: u32,
+            flags: u32,
+        ) -> u32;
+    }
+
+    // the longest path Windows supports, in wide characters
+    let mut buffer = vec![0u16; 0x8000];
+    // flags 0: normalized with a drive letter, i.e. `\\?\C:\...`
+    let len =
+        unsafe { GetFinalPathNameByHandleW(handle, buffer.as_mut_ptr(), buffer.len() as u32, 0) }
+            as usize;
+    if len == 0 || len >= buffer.len() {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(PathBuf::from(std::ffi::OsString::from_wide(&buffer[..len])))
+}
@@ -1,9 +1,22 @@
+use super::archive::find_volumes;
+use super::blocks;
 use super::error::*;
+use super::hash::{HashKind, Hasher, StoredHash};
+use super::memory::MemoryFile;
+use super::pattern::Pattern;
 use super::*;
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::io::{Read, Write};
+use std::iter::FusedIterator;
+use std::ops::{Bound, RangeBounds};
 use std::os::raw::{c_int, c_uint};
 use std::path::{Path, PathBuf};
 use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 bitflags::bitflags! {
     #[derive(Debug, Default)]
@@ -31,9 +44,80 @@ pub enum VolumeInfo {
     Subsequent,
 }
 
+/// How the volumes of a multipart archive are named, see [`OpenArchive::volume_naming`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeNaming {
+    /// `name.part1.rar`, `name.part2.rar`, …, the default since RAR 3.0
+    New,
+    /// `name.rar`, `name.r00`, `name.r01`, …, used by older versions and `rar -vn`
+    Old,
+}
+
+/// The generation of the RAR format an archive is stored in, see [`OpenArchive::format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RarFormat {
+    /// RAR 1.4, signature `RE~^`
+    Rar14,
+    /// RAR 1.5 up to RAR 4, signature `Rar!\x1a\x07\x00`
+    Rar15,
+    /// RAR 5 and later, signature `Rar!\x1a\x07\x01\x00`
+    Rar50,
+    /// a signature of a future format, or the archive could not be read
+    Unknown,
+}
+
+/// The authenticity verification of an archive, see [`OpenArchive::authenticity`].
+///
+/// No UnRAR library version reports it yet, so further fields may be added once one does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AuthenticityInfo {
+    /// the name of the creator the archive was signed by
+    pub name: String,
+    /// when the archive was signed, if recorded
+    pub date: Option<SystemTime>,
+}
+
+/// How an archive is encrypted, see [`OpenArchive::encryption_info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncryptionInfo {
+    /// the cipher the data (and headers) are encrypted with
+    pub method: EncryptionMethod,
+    /// the number of PBKDF2 iterations deriving the key from the password, only stored by
+    /// RAR5 archives. RAR 2.9 to 4 always hash the password 0x40000 times.
+    pub kdf_iterations: Option<u32>,
+    /// whether the headers are encrypted as well (`rar -hp`), not only the data of the entries
+    pub encrypted_headers: bool,
+}
+
+/// The cipher an archive is encrypted with, `CRYPT_METHOD` in the UnRAR sources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncryptionMethod {
+    /// the proprietary cipher of RAR 1.3
+    Rar13,
+    /// the proprietary cipher of RAR 1.5
+    Rar15,
+    /// the proprietary cipher of RAR 2.0
+    Rar20,
+    /// AES-128, used by RAR 2.9 up to RAR 4
+    Aes128,
+    /// AES-256, used by RAR 5 and later
+    Aes256,
+}
+
+/// The UnRAR library looks for the signature of self-extracting archives within
+/// this many bytes, `MAXSFXSIZE` in the UnRAR sources.
+pub(crate) const MAX_SFX_SIZE: u64 = 0x200000;
+
 #[derive(Debug)]
 struct Handle(NonNull<native::Handle>);
 
+// SAFETY: the UnRAR library keeps all state of an open archive behind its handle rather
+// than in thread-local storage, so it may be used from any thread, but not from several
+// at once. Neither `Handle` nor `OpenArchive` is `Sync`, and every call takes `&mut self`
+// or consumes the archive.
+unsafe impl Send for Handle {}
+
 impl Drop for Handle {
     fn drop(&mut self) {
         unsafe { native::RARCloseArchive(self.0.as_ptr() as *const _) };
@@ -43,15 +127,447 @@ impl Drop for Handle {
 /// An open RAR archive that can be read or processed.
 ///
 /// See the [OpenArchive chapter](index.html#openarchive) for more information.
+///
+/// An `OpenArchive` is `Send`, so it can be opened on one thread and processed on another,
+/// which is why callbacks must be `Send` as well. It is not `Sync`: the UnRAR library does
+/// not support using the same archive from several threads at once.
 #[derive(Debug)]
 pub struct OpenArchive<M: OpenMode, C: Cursor> {
     handle: Handle,
+    // must be dropped after `handle`
+    memory: Option<MemoryFile>,
+    callbacks: Box<Callbacks>,
+    // kept to reopen the archive, e.g. to read the comment
+    path: PathBuf,
+    password: Option<Vec<u8>>,
     flags: ArchiveFlags,
     damaged: bool,
+    /// whether the end of the archive was reached while iterating
+    finished: bool,
     extra: C,
     marker: std::marker::PhantomData<M>,
 }
-type Userdata<T> = (T, Option<widestring::WideCString>);
+struct Userdata<'a, T> {
+    data: T,
+    volume: Option<widestring::WideCString>,
+    callbacks: &'a mut Callbacks,
+    /// the entry being processed, if any
+    entry: Option<&'a FileHeader>,
+    /// a panic caught in the callback, to be resumed once back from the UnRAR library
+    panic: Option<Box<dyn Any + Send>>,
+    /// why processing was aborted from the callback, e.g. by the cancellation token
+    aborted: Option<Code>,
+    /// bytes of the entry unpacked so far
+    unpacked: u64,
+}
+
+impl<'a, T> Userdata<'a, T> {
+    fn new(data: T, callbacks: &'a mut Callbacks, entry: Option<&'a FileHeader>) -> Self {
+        Userdata {
+            data,
+            volume: None,
+            callbacks,
+            entry,
+            panic: None,
+            aborted: None,
+            unpacked: 0,
+        }
+    }
+
+    /// resumes unwinding if a callback panicked, which must not unwind across the FFI boundary.
+    fn resume_panic(&mut self) {
+        if let Some(panic) = self.panic.take() {
+            std::panic::resume_unwind(panic);
+        }
+    }
+}
+
+/// A closure supplying the password when the UnRAR library asks for it, see
+/// [`Archive::with_password_callback`](crate::Archive::with_password_callback).
+pub(crate) type PasswordCallback = Box<dyn FnMut(usize) -> Option<Vec<u8>> + Send>;
+
+/// A closure locating a missing volume, see
+/// [`Archive::on_next_volume`](crate::Archive::on_next_volume).
+pub(crate) type VolumeCallback = Box<dyn FnMut(&Path) -> Option<PathBuf> + Send>;
+
+/// A closure notified about unpacked data, see
+/// [`Archive::on_progress`](crate::Archive::on_progress).
+pub(crate) type ProgressCallback = Box<dyn FnMut(&FileHeader, usize) + Send>;
+
+/// Initial size of the buffer (in wide characters) the archive comment is read into.
+const COMMENT_BUFFER_SIZE: usize = 0x1000;
+/// The comment buffer grows up to 16 MiB, beyond that reading comments fails with `SmallBuf`.
+const MAX_COMMENT_BUFFER_SIZE: usize = 0x100_0000 / std::mem::size_of::<native::WCHAR>();
+
+/// Size of the buffer (in wide characters) the UnRAR library passes along with
+/// `UCM_CHANGEVOLUMEW`, `MAXPATHSIZE` in the UnRAR sources.
+const VOLUME_BUFFER_SIZE: usize = 0x10000;
+/// Size of that buffer before UnRAR 7, `NM` in the UnRAR sources of 5.x and 6.x.
+const NM_VOLUME_BUFFER_SIZE: usize = 2048;
+/// Size of that buffer before UnRAR 5, `NM` in the UnRAR sources of 3.x and 4.x.
+const OLD_NM_VOLUME_BUFFER_SIZE: usize = 1024;
+
+/// the size of the buffer the linked UnRAR library passes along with `UCM_CHANGEVOLUMEW`,
+/// which depends on its version when the system's library is linked without the `static`
+/// feature. Writing a longer path into it would overflow it.
+fn volume_buffer_size() -> usize {
+    match dll::dll_version() {
+        // UnRAR 7
+        9.. => VOLUME_BUFFER_SIZE,
+        // UnRAR 5 and 6
+        6..=8 => NM_VOLUME_BUFFER_SIZE,
+        _ => OLD_NM_VOLUME_BUFFER_SIZE,
+    }
+}
+
+/// Size of the buffer (in wide characters) link targets are read into, `MAXPATHSIZE`
+/// in the UnRAR sources.
+const REDIR_NAME_BUFFER_SIZE: usize = 0x10000;
+
+/// Size of the buffer (in bytes) file comments are read into, longer ones are truncated.
+const FILE_COMMENT_BUFFER_SIZE: usize = 0x1000;
+
+/// User-provided callbacks and options, shared between the archive and the UnRAR library.
+#[derive(Default)]
+pub(crate) struct Callbacks {
+    pub(crate) password: Option<PasswordCallback>,
+    password_requests: usize,
+    /// the last password returned by the password callback
+    supplied_password: Option<Vec<u8>>,
+    pub(crate) volume: Option<VolumeCallback>,
+    pub(crate) progress: Option<ProgressCallback>,
+    pub(crate) cancellation: Option<Arc<AtomicBool>>,
+    pub(crate) overwrite: OverwritePolicy,
+    /// whether to allow entries escaping the directory they are extracted into
+    pub(crate) unsanitized: bool,
+    pub(crate) max_total_unpacked: Option<u64>,
+    pub(crate) max_entry_unpacked: Option<u64>,
+    /// whether to keep the data read from a corrupted entry in the error
+    pub(crate) allow_partial: bool,
+    /// whether extracting all entries goes on after an entry failed
+    pub(crate) continue_on_error: bool,
+    /// whether to create hard links to the files extracted before instead of through the library
+    pub(crate) recreate_links: bool,
+    /// whether to read the archive comment while opening
+    pub(crate) comment_on_open: bool,
+    /// the number of leading path components removed from entries extracted into a directory
+    pub(crate) strip_components: usize,
+    /// the unpacked to packed size ratio beyond which a summary is suspicious, if not the default
+    pub(crate) suspicious_ratio: Option<u64>,
+    /// the archive comment read while opening
+    comment: Option<Vec<u8>>,
+    /// the archive comment decoded by the first call to `comment_lines`
+    comment_string: std::cell::OnceCell<String>,
+    /// bytes unpacked from all entries so far
+    total_unpacked: u64,
+    /// the volume the last entry was read from
+    last_volume: Option<PathBuf>,
+    /// the buffers headers are read into, allocated by the first header read
+    header_buffers: Option<HeaderBuffers>,
+}
+
+/// The buffers the UnRAR library writes the link target and the comment of an entry into,
+/// kept across headers so that reading each one does not allocate them anew.
+struct HeaderBuffers {
+    redir_name: Vec<native::WCHAR>,
+    comment: Vec<u8>,
+}
+
+impl Default for HeaderBuffers {
+    fn default() -> Self {
+        HeaderBuffers {
+            redir_name: vec![0; REDIR_NAME_BUFFER_SIZE],
+            comment: vec![0; FILE_COMMENT_BUFFER_SIZE],
+        }
+    }
+}
+
+impl fmt::Debug for Callbacks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Callbacks")
+            .field("password", &self.password.as_ref().map(|_| ".."))
+            .field("password_requests", &self.password_requests)
+            .field(
+                "supplied_password",
+                &self.supplied_password.as_ref().map(|_| ".."),
+            )
+            .field("volume", &self.volume.as_ref().map(|_| ".."))
+            .field("progress", &self.progress.as_ref().map(|_| ".."))
+            .field("cancellation", &self.cancellation)
+            .field("overwrite", &self.overwrite)
+            .field("unsanitized", &self.unsanitized)
+            .field("max_total_unpacked", &self.max_total_unpacked)
+            .field("max_entry_unpacked", &self.max_entry_unpacked)
+            .field("allow_partial", &self.allow_partial)
+            .field("continue_on_error", &self.continue_on_error)
+            .field("recreate_links", &self.recreate_links)
+            .field("comment_on_open", &self.comment_on_open)
+            .field("strip_components", &self.strip_components)
+            .field("suspicious_ratio", &self.suspicious_ratio)
+            .field("comment", &self.comment)
+            .field("comment_string", &self.comment_string)
+            .field("total_unpacked", &self.total_unpacked)
+            .field("last_volume", &self.last_volume)
+            .field(
+                "header_buffers",
+                &self.header_buffers.as_ref().map(|_| ".."),
+            )
+            .finish()
+    }
+}
+
+impl Callbacks {
+    /// whether the cancellation token was triggered.
+    fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(|token| token.load(Ordering::Relaxed))
+    }
+
+    /// counts `bytes` just unpacked, `entry_unpacked` in total from the current entry.
+    /// Returns `false` if a size limit is exceeded.
+    fn count_unpacked(&mut self, entry_unpacked: u64, bytes: u64) -> bool {
+        self.total_unpacked += bytes;
+        let within = |max: Option<u64>, unpacked| max.is_none_or(|max| unpacked <= max);
+        within(self.max_entry_unpacked, entry_unpacked)
+            && within(self.max_total_unpacked, self.total_unpacked)
+    }
+
+    /// writes the password returned from the password callback into `buffer`
+    /// (of length `size`). Returns `false` if no password could be obtained.
+    fn request_password(&mut self, buffer: *mut native::WCHAR, size: usize) -> bool {
+        let Some(callback) = self.password.as_mut() else {
+            return false;
+        };
+        self.password_requests += 1;
+        let Some(password) = callback(self.password_requests) else {
+            return false;
+        };
+        let password = self.supplied_password.insert(password);
+        let password = widestring::WideString::from_str(&String::from_utf8_lossy(password));
+        let len = password.len().min(size.saturating_sub(1));
+        write_wide(buffer, size, &password.as_slice()[..len])
+    }
+
+    /// asks the volume callback where to find the missing volume whose expected
+    /// path is in `buffer` (of length `size`), and writes the answer back into it.
+    /// Returns `false` if the volume could not be located.
+    fn request_volume(&mut self, buffer: *mut native::WCHAR, size: usize) -> bool {
+        let Some(callback) = self.volume.as_mut() else {
+            return false;
+        };
+        let expected = unsafe { widestring::WideCStr::from_ptr_truncate(buffer as *const _, size) };
+        let Ok(expected) = expected else {
+            return false;
+        };
+        let Some(next) = callback(Path::new(&expected.to_os_string())) else {
+            return false;
+        };
+        match widestring::WideCString::from_os_str(next) {
+            // the path must fit into the buffer, truncating it would point elsewhere
+            Ok(next) if next.len() < size => write_wide(buffer, size, next.as_slice()),
+            _ => false,
+        }
+    }
+}
+
+/// What to do when extracting an entry to a path where a file already exists,
+/// see [`Archive::with_overwrite`](crate::Archive::with_overwrite).
+///
+/// Directories are always extracted into existing ones.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OverwritePolicy {
+    /// replace the existing file
+    #[default]
+    Overwrite,
+    /// keep the existing file, not extracting the entry
+    Skip,
+    /// extract the entry next to the existing file, appending the first free number
+    /// to its name, e.g. `file(1).txt`
+    Rename,
+}
+
+/// What extracting an entry into a directory did according to the [`OverwritePolicy`],
+/// see [`OpenArchive::extract_all_to`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtractOutcome {
+    /// the entry was extracted to its path, replacing an existing file with
+    /// [`OverwritePolicy::Overwrite`]
+    Written,
+    /// the entry was not extracted, as a file already exists at its path with
+    /// [`OverwritePolicy::Skip`], or nothing is left of its path after
+    /// [stripping](crate::Archive::with_strip_components) it
+    Skipped,
+    /// the entry was extracted next to an existing file with [`OverwritePolicy::Rename`],
+    /// to the path given
+    Renamed(PathBuf),
+}
+
+/// The format of the archive starting with `prefix`, read from its signature without opening it,
+/// or `None` if `prefix` does not start with a RAR signature.
+///
+/// Only the first 8 bytes are looked at, so a header just read from a file or a socket is
+/// enough. Unlike [`OpenArchive::format`] this does not search for the signature after a
+/// self-extracting module. A signature of a future format gives [`RarFormat::Unknown`].
+///
+/// # Example
+///
+/// ```
+/// use unrar::{detect_format, RarFormat};
+///
+/// assert_eq!(detect_format(b"Rar!\x1a\x07\x01\x00"), Some(RarFormat::Rar50));
+/// let data = std::fs::read("data/version.rar").unwrap();
+/// assert_eq!(detect_format(&data), Some(RarFormat::Rar15));
+/// assert_eq!(detect_format(b"PK\x03\x04"), None);
+/// ```
+pub fn detect_format(prefix: &[u8]) -> Option<RarFormat> {
+    find_signature(prefix.get(..8).unwrap_or(prefix))
+        .filter(|&(offset, _)| offset == 0)
+        .map(|(_, format)| format)
+}
+
+/// What to do with an entry, as decided by the hook passed to
+/// [`OpenArchive::with_header_hook`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryAction {
+    /// extract the entry and go on with the next one
+    Extract,
+    /// skip the entry without extracting it and go on with the next one
+    Skip,
+    /// stop processing the archive, failing with [`Code::Cancelled`]
+    Abort,
+}
+
+/// the format of the first RAR signature in `data`, see `Archive::IsSignature`
+/// in the UnRAR sources.
+fn signature_format(data: &[u8]) -> RarFormat {
+    find_signature(data).map_or(RarFormat::Unknown, |(_, format)| format)
+}
+
+/// the offset and format of the first RAR signature in `data`.
+pub(crate) fn find_signature(data: &[u8]) -> Option<(usize, RarFormat)> {
+    (0..data.len())
+        .filter(|&i| data[i] == b'R')
+        .find_map(|i| match &data[i..] {
+            [_, b'E', b'~', b'^', ..] => Some((i, RarFormat::Rar14)),
+            [_, b'a', b'r', b'!', 0x1a, 0x07, 0, ..] => Some((i, RarFormat::Rar15)),
+            [_, b'a', b'r', b'!', 0x1a, 0x07, 1, ..] => Some((i, RarFormat::Rar50)),
+            [_, b'a', b'r', b'!', 0x1a, 0x07, 2..=4, ..] => Some((i, RarFormat::Unknown)),
+            _ => None,
+        })
+}
+
+/// whether Windows opens something else than a file named `name` in the directory: a device
+/// for reserved names like `CON` or `nul.txt` (ignoring the extension and trailing spaces),
+/// or an alternate data stream of a file for names like `file.txt:stream`.
+fn is_special_windows_name(name: &std::ffi::OsStr) -> bool {
+    let name = name.to_string_lossy();
+    if name.contains(':') {
+        return true;
+    }
+    let stem = name
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches(' ');
+    let stem = stem.to_ascii_uppercase();
+    matches!(
+        stem.as_bytes(),
+        b"CON"
+            | b"PRN"
+            | b"AUX"
+            | b"NUL"
+            | b"CONIN$"
+            | b"CONOUT$"
+            | [b'C', b'O', b'M', b'0'..=b'9']
+            | [b'L', b'P', b'T', b'0'..=b'9']
+    )
+}
+
+/// where to extract an entry to, according to the [`OverwritePolicy`].
+enum Target {
+    Keep,
+    Skip,
+    Rename(PathBuf),
+}
+
+/// whether `path` stays within the directory it is joined to, i.e. it is relative
+/// (without a drive letter or `\\?\` prefix on Windows) and does not climb above
+/// it with `..`. On Windows, its components must not be [device names or name
+/// streams](is_special_windows_name) either.
+fn is_contained(path: &Path) -> bool {
+    use std::path::Component;
+    let mut depth = 0usize;
+    path.components().all(|component| match component {
+        Component::Normal(name) if cfg!(windows) && is_special_windows_name(name) => false,
+        Component::Normal(_) => {
+            depth += 1;
+            true
+        }
+        Component::CurDir => true,
+        Component::ParentDir => depth.checked_sub(1).map(|d| depth = d).is_some(),
+        Component::RootDir | Component::Prefix(_) => false,
+    })
+}
+
+/// the first path next to `dest` with a number appended to its name that does not exist yet.
+fn renamed(dest: &Path) -> PathBuf {
+    let stem = dest.file_stem().unwrap_or_default();
+    (1..)
+        .map(|n| {
+            let mut name = stem.to_os_string();
+            name.push(format!("({n})"));
+            if let Some(extension) = dest.extension() {
+                name.push(".");
+                name.push(extension);
+            }
+            dest.with_file_name(name)
+        })
+        .find(|path| path.symlink_metadata().is_err())
+        .unwrap()
+}
+
+/// a hidden path next to `dest` to extract to before renaming it to `dest`, unique
+/// within this process.
+fn temporary_sibling(dest: &Path) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let mut name = std::ffi::OsString::from(".");
+    name.push(dest.file_name().unwrap_or_default());
+    name.push(format!(
+        ".unrar-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    dest.with_file_name(name)
+}
+
+/// copies `value` into the nul-terminated wide string `buffer` of length `size`.
+/// `value` must be shorter than `size`.
+fn write_wide(buffer: *mut native::WCHAR, size: usize, value: &[widestring::WideChar]) -> bool {
+    let buffer = unsafe { std::slice::from_raw_parts_mut(buffer, size) };
+    for (dest, c) in buffer.iter_mut().zip(value) {
+        *dest = *c as native::WCHAR;
+    }
+    buffer[value.len()] = 0;
+    true
+}
+
+/// the destination path and name of an entry as passed to the UnRAR library.
+type ExtractTarget = (Option<pathed::RarString>, Option<pathed::RarString>);
+
+/// a processed file's header and the archive, ready to read the next header.
+type Processed<M> = (FileHeader, OpenArchive<M, CursorBeforeHeader>);
+
+/// the data of the entries read by name and the names not found.
+type ExtractedMany = (HashMap<PathBuf, Vec<u8>>, HashSet<PathBuf>);
+
+/// a processed file's header and the result of processing it, along with the archive
+/// if the next header can be read after that result.
+pub(crate) type Continued<T = ()> = (
+    FileHeader,
+    UnrarResult<T>,
+    Option<OpenArchive<Process, CursorBeforeHeader>>,
+);
 
 mod private {
     use super::native;
@@ -140,31 +656,304 @@ impl OpenMode for ListSplit {
 }
 
 impl<Mode: OpenMode, C: Cursor> OpenArchive<Mode, C> {
-    /// is the archive locked
+    /// is the archive locked (`rar -k`), i.e. it must not be modified anymore.
+    ///
+    /// Reads the `ROADF_LOCK` (0x04) archive flag, which the UnRAR library sets from the main
+    /// archive header: `MHD_LOCK` (0x04) in RAR4 archives and `MHFL_LOCK` (0x10) in RAR5
+    /// archives. Only tools changing archives have to respect it, reading them is unaffected.
     pub fn is_locked(&self) -> bool {
         self.flags.contains(ArchiveFlags::LOCK)
     }
 
-    /// are the archive headers encrypted
+    /// are the archive headers encrypted, i.e. even the filenames are only readable
+    /// with the password (`rar -hp`).
+    ///
+    /// Reads the `ROADF_ENCHEADERS` (0x80) archive flag. This does not require the password,
+    /// so it can be used to ask for it before listing fails with
+    /// [`Code::MissingPassword`](crate::error::Code::MissingPassword).
+    /// Whether individual entries are encrypted is told by [`FileHeader::is_encrypted`].
+    #[doc(alias = "is_headers_encrypted")]
     pub fn has_encrypted_headers(&self) -> bool {
         self.flags.contains(ArchiveFlags::ENC_HEADERS)
     }
 
-    /// does the archive have a recovery record
+    /// does the archive have a recovery record, so `rar r` can repair it if damaged.
+    ///
+    /// Reads the `ROADF_RECOVERY` (0x40) archive flag, which the UnRAR library sets from the
+    /// main archive header: `MHD_PROTECT` (0x40) in RAR4 archives and `MHFL_PROTECT` (0x08)
+    /// in RAR5 archives. The recovery record itself is not read or checked.
     pub fn has_recovery_record(&self) -> bool {
         self.flags.contains(ArchiveFlags::RECOVERY)
     }
 
+    /// the authenticity verification (`rar -av`) naming the creator of the archive, or `None`
+    /// if the UnRAR library does not report it.
+    ///
+    /// Only RAR 2.x to 4 archives carry such a block, RAR 5 dropped it. The UnRAR library only
+    /// exempts it from the header CRC check and skips it, neither `RAROpenArchiveEx` nor
+    /// `RARReadHeaderEx` tell its contents, and its layout is not documented, so this always
+    /// returns `None` for now. It never fails, so it is safe to call for any archive, and will
+    /// return the creator once a library version exposes it. The `ROADF_SIGNED` archive flag
+    /// only tells that the main archive header points to such a block, not who signed it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::Archive;
+    ///
+    /// let archive = Archive::new("data/version.rar").open_for_listing().unwrap();
+    /// assert_eq!(archive.authenticity(), None);
+    /// ```
+    pub fn authenticity(&self) -> Option<AuthenticityInfo> {
+        None
+    }
+
+    /// does the archive have a quick open record (`rar -qo`), a copy of the file headers
+    /// stored at the end of RAR5 archives so they can be listed without seeking through
+    /// the whole archive.
+    ///
+    /// The UnRAR library reports no flag for it, so this reads the locator of the main archive
+    /// header. The library already uses the record when listing, falling back to reading the
+    /// headers one by one without it: nothing has to be enabled to benefit from it. `false`
+    /// for RAR4 archives, which have none, and for archives with
+    /// [encrypted headers](Self::has_encrypted_headers).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::Archive;
+    ///
+    /// let archive = Archive::new("data/quickopen.rar").open_for_listing().unwrap();
+    /// assert!(archive.has_quick_open());
+    /// assert_eq!(archive.count(), 3);
+    /// ```
+    pub fn has_quick_open(&self) -> bool {
+        blocks::has_quick_open(&self.path)
+    }
+
+    /// how the archive is encrypted, or `None` if neither its headers nor any entry are.
+    ///
+    /// The UnRAR library does not report it, so this reads the encryption header of archives
+    /// with [encrypted headers](Self::has_encrypted_headers), and otherwise the headers up to
+    /// the first encrypted entry, of which the encryption is returned. The password is not
+    /// needed for that. Fails with [`Code::Unsupported`] for RAR 1.4 archives and future
+    /// formats, with [`Code::BadArchive`] if a header is damaged, e.g. its sizes point beyond
+    /// the end of the archive, or with [`Code::ERead`] if the archive cannot be read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::{Archive, EncryptionMethod};
+    ///
+    /// let archive = Archive::new("data/comment-hpw-password.rar").open_for_listing();
+    /// let info = archive.unwrap().encryption_info().unwrap().unwrap();
+    /// assert_eq!(info.method, EncryptionMethod::Aes256);
+    /// assert_eq!(info.kdf_iterations, Some(1 << 15));
+    /// assert!(info.encrypted_headers);
+    ///
+    /// let archive = Archive::new("data/version.rar").open_for_listing().unwrap();
+    /// assert_eq!(archive.encryption_info().unwrap(), None);
+    /// ```
+    pub fn encryption_info(&self) -> UnrarResult<Option<EncryptionInfo>> {
+        blocks::encryption(&self.path).map_err(|e| {
+            let code = match e.kind() {
+                std::io::ErrorKind::Unsupported => Code::Unsupported,
+                std::io::ErrorKind::InvalidData => Code::BadArchive,
+                _ => Code::ERead,
+            };
+            UnrarError::from(code, When::Read).with_source(e)
+        })
+    }
+
+    /// the size in bytes of the largest dictionary the entries of the archive were compressed
+    /// with, which is about the memory needed to unpack them, or `None` for RAR4 archives.
+    ///
+    /// The UnRAR library does not report it before reading the headers, so this reads the
+    /// compression information of the RAR5 file headers, like
+    /// [`FileHeader::dictionary_size`] does for a single entry. A dictionary may be up to
+    /// 1 GiB for RAR 5.0 and more for RAR 7.0. RAR4 archives store no size in their headers,
+    /// only a flag choosing one from 64 KiB up to 4 MiB. `None` as well for archives without
+    /// files, with [encrypted headers](Self::has_encrypted_headers), or if they cannot be read.
+    /// Check it before extracting in memory-constrained environments.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::Archive;
+    ///
+    /// let archive = Archive::new("data/solid.rar").open_for_listing().unwrap();
+    /// assert_eq!(archive.dictionary_size(), Some(1 << 20));
+    /// let archive = Archive::new("data/version.rar").open_for_listing().unwrap();
+    /// assert_eq!(archive.dictionary_size(), None);
+    /// ```
+    pub fn dictionary_size(&self) -> Option<u64> {
+        blocks::dictionary_size(&self.path)
+    }
+
     /// does the archive have comments
     pub fn has_comment(&self) -> bool {
         self.flags.contains(ArchiveFlags::COMMENT)
     }
 
+    /// Reads the archive comment, empty if the archive has none.
+    ///
+    /// RAR5 comments are UTF-8, older ones may use any (OEM) encoding. Characters the UnRAR
+    /// library could not decode are returned as the bytes found in the archive, so the comment
+    /// can be decoded with the right encoding if it is not valid UTF-8.
+    ///
+    /// The UnRAR library only reads the comment while opening, so this reopens the archive
+    /// unless it was [read on open](crate::Archive::with_comment_on_open).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::Archive;
+    ///
+    /// let archive = Archive::new("data/comment.rar").open_for_listing().unwrap();
+    /// assert_eq!(archive.comment_bytes().unwrap(), b"abcdef12345\n");
+    /// ```
+    pub fn comment_bytes(&self) -> UnrarResult<Vec<u8>> {
+        self.read_comment(COMMENT_BUFFER_SIZE, MAX_COMMENT_BUFFER_SIZE)
+    }
+
+    /// reads the comment with a buffer of `size` wide characters, doubling it
+    /// up to `max_size` while the comment does not fit.
+    fn read_comment(&self, mut size: usize, max_size: usize) -> UnrarResult<Vec<u8>> {
+        if !self.has_comment() {
+            return Ok(Vec::new());
+        }
+        if let Some(comment) = &self.callbacks.comment {
+            return Ok(comment.clone());
+        }
+        // archives with encrypted headers need the password for the comment
+        let password = (self.password.as_ref())
+            .or(self.callbacks.supplied_password.as_ref())
+            .cloned();
+        let mut callbacks = Callbacks {
+            password: Some(Box::new(move |attempt| {
+                password.clone().filter(|_| attempt == 1)
+            })),
+            ..Default::default()
+        };
+        loop {
+            let mut buffer = vec![0; size];
+            let (handle, data) = open_handle(
+                &self.path,
+                native::RAR_OM_LIST,
+                &mut callbacks,
+                Some(&mut buffer),
+            )?;
+            drop(handle);
+            match Code::from(data.open_result as i32) {
+                Code::Success => {}
+                code => return Err(UnrarError::from(code, When::Open)),
+            }
+            // `comment_size` includes the terminating nul
+            let len = (data.comment_size as usize).saturating_sub(1);
+            match data.comment_state {
+                0 => return Ok(Vec::new()),
+                1 => return Ok(wide_to_bytes(&buffer[..len])),
+                state => match Code::from(state as i32) {
+                    // the library does not tell the size it needs, so retry with a larger buffer
+                    Code::SmallBuf if size < max_size => size = (size * 2).min(max_size),
+                    code => return Err(UnrarError::from(code, When::Open)),
+                },
+            }
+        }
+    }
+
+    /// Reads the archive comment, see [`comment_bytes`](Self::comment_bytes), replacing
+    /// invalid UTF-8 with [`U+FFFD REPLACEMENT CHARACTER`](char::REPLACEMENT_CHARACTER).
+    pub fn comment_string(&self) -> UnrarResult<String> {
+        self.comment_bytes()
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// Reads the archive comment like [`comment_string`](Self::comment_string) and splits it
+    /// into lines ending with either `\n` or `\r\n`, which are not part of the lines.
+    ///
+    /// An archive without a comment has no lines. A line ending at the end of the comment
+    /// does not start another line. The comment is read and decoded once, and kept by the
+    /// archive for later calls. If it cannot be read, there are no lines either: use
+    /// [`comment_string`](Self::comment_string) to tell why.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let archive = Archive::new("data/comment.rar").open_for_listing().unwrap();
+    /// let lines: Vec<_> = archive.comment_lines().collect();
+    /// assert_eq!(lines, ["abcdef12345"]);
+    /// ```
+    pub fn comment_lines(&self) -> impl Iterator<Item = &str> {
+        let cache = &self.callbacks.comment_string;
+        if cache.get().is_none() {
+            // failures are not kept, so a later call tries again
+            if let Ok(comment) = self.comment_string() {
+                let _ = cache.set(comment);
+            }
+        }
+        cache.get().map_or("", String::as_str).lines()
+    }
+
     /// is the archive solid (all files in a single compressed block).
+    ///
+    /// Entries of a solid archive can only be unpacked in order, so skipping to an entry
+    /// (e.g. with [`Archive::extract_file`](crate::Archive::extract_file)) still has to
+    /// decompress everything before it. Check this before extracting a few late entries
+    /// of a large archive.
     pub fn is_solid(&self) -> bool {
         self.flags.contains(ArchiveFlags::SOLID)
     }
 
+    /// the path the archive was opened from, or `None` for archives opened
+    /// [from memory](crate::Archive::from_bytes).
+    ///
+    /// Like [`volume_info`](Self::volume_info), this is the file that was *initially* opened,
+    /// even after advancing to a different volume.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use unrar::Archive;
+    ///
+    /// let archive = Archive::new("data/version.rar").open_for_listing().unwrap();
+    /// assert_eq!(archive.path(), Some(Path::new("data/version.rar")));
+    /// let data = std::fs::read("data/version.rar").unwrap();
+    /// let archive = Archive::from_bytes(data).open_for_listing().unwrap();
+    /// assert_eq!(archive.path(), None);
+    /// ```
+    pub fn path(&self) -> Option<&Path> {
+        self.memory.is_none().then_some(self.path.as_path())
+    }
+
+    /// Closes the archive and returns the buffer it was opened from with
+    /// [`Archive::from_bytes`](crate::Archive::from_bytes), e.g. to reuse it for the next
+    /// archive. `None` for archives opened from a file.
+    ///
+    /// The UnRAR library reads from a copy of the data, so the buffer is returned as it was
+    /// passed in, after the archive handle was closed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::Archive;
+    ///
+    /// let data = std::fs::read("data/version.rar").unwrap();
+    /// # #[cfg(target_os = "linux")]
+    /// # {
+    /// let mut archive = Archive::from_bytes(data.clone()).open_for_listing().unwrap();
+    /// assert!(archive.next().unwrap().is_ok());
+    /// assert_eq!(archive.into_bytes(), Some(data));
+    /// # }
+    /// ```
+    pub fn into_bytes(self) -> Option<Vec<u8>> {
+        let OpenArchive { handle, memory, .. } = self;
+        drop(handle);
+        memory.map(MemoryFile::into_bytes)
+    }
+
     /// Volume information on the file that was *initially* opened.
     ///
     /// returns
@@ -184,6 +973,124 @@ impl<Mode: OpenMode, C: Cursor> OpenArchive<Mode, C> {
         }
     }
 
+    /// is the archive the first volume of a multipart archive, which extracting has to start
+    /// with. Entries continued from a previous volume cannot be processed otherwise.
+    ///
+    /// Reads the `ROADF_FIRSTVOLUME` (0x100) archive flag. RAR5 archives store the volume
+    /// number, RAR 3.0 and later set `MHD_FIRSTVOLUME` in the main archive header, and for
+    /// older archives the UnRAR library derives it from the first entry not being continued.
+    /// See [`Archive::first_volume_path`] to find the first volume.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::Archive;
+    ///
+    /// let archive = Archive::new("data/volumes.part1.rar").open_for_listing().unwrap();
+    /// assert!(archive.is_first_volume());
+    /// let archive = Archive::new("data/volumes.part2.rar").open_for_listing().unwrap();
+    /// assert!(!archive.is_first_volume());
+    /// ```
+    pub fn is_first_volume(&self) -> bool {
+        self.flags.contains(ArchiveFlags::FIRST_VOLUME)
+    }
+
+    /// is the archive a volume of a multipart archive, whether the first or any other.
+    pub fn is_volume(&self) -> bool {
+        self.flags.contains(ArchiveFlags::VOLUME)
+    }
+
+    /// the naming scheme of the volumes, or `None` if the archive is not a
+    /// [volume](Self::is_volume).
+    ///
+    /// This is stored in the archive header, but volumes of the [new](VolumeNaming::New)
+    /// naming renamed to `name.rNN` are found like those of the [old](VolumeNaming::Old) one,
+    /// which is reported for them instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::{Archive, VolumeNaming};
+    ///
+    /// let archive = Archive::new("data/volumes.part1.rar").open_for_listing().unwrap();
+    /// assert_eq!(archive.volume_naming(), Some(VolumeNaming::New));
+    /// let archive = Archive::new("data/oldnaming.r00").open_for_listing().unwrap();
+    /// assert_eq!(archive.volume_naming(), Some(VolumeNaming::Old));
+    /// ```
+    pub fn volume_naming(&self) -> Option<VolumeNaming> {
+        if !self.is_volume() {
+            return None;
+        }
+        // `rNN`, or `NNN` for volumes split by other tools
+        let renamed = (self.path.extension())
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                ext.len() == 3 && ext != "rar" && ext[1..].bytes().all(|c| c.is_ascii_digit())
+            });
+        match self.flags.contains(ArchiveFlags::NEW_NUMBERING) && !renamed {
+            true => Some(VolumeNaming::New),
+            false => Some(VolumeNaming::Old),
+        }
+    }
+
+    /// the generation of the RAR format the archive is stored in, read from its signature
+    /// like the UnRAR library does, searching past the executable of self-extracting archives.
+    ///
+    /// BLAKE2sp hashes, Windows symbolic links and junctions, file references, high resolution
+    /// times and dictionaries larger than 4 MiB are only found in [`RarFormat::Rar50`] archives.
+    /// Returns [`RarFormat::Unknown`] if the archive cannot be read anymore.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::{Archive, RarFormat};
+    ///
+    /// let archive = Archive::new("data/version.rar").open_for_listing().unwrap();
+    /// assert_eq!(archive.format(), RarFormat::Rar15);
+    /// let archive = Archive::new("data/comment.rar").open_for_listing().unwrap();
+    /// assert_eq!(archive.format(), RarFormat::Rar50);
+    /// ```
+    pub fn format(&self) -> RarFormat {
+        let mut data = Vec::new();
+        let read = std::fs::File::open(&self.path)
+            .and_then(|file| file.take(MAX_SFX_SIZE).read_to_end(&mut data));
+        match read {
+            Ok(_) => signature_format(&data),
+            Err(_) => RarFormat::Unknown,
+        }
+    }
+
+    /// the number of volumes of the multipart archive found on disk, by probing for files named
+    /// like the first volume with consecutive numbers, or `None` if the archive is not a
+    /// [volume](Self::is_volume) or its first volume cannot be found.
+    ///
+    /// Volumes after a missing one are not counted. Whether the last volume found is the last
+    /// of the archive is only known once it has been read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::Archive;
+    ///
+    /// let archive = Archive::new("data/volumes.part2.rar").open_for_listing().unwrap();
+    /// assert_eq!(archive.volume_count(), Some(3));
+    /// ```
+    pub fn volume_count(&self) -> Option<usize> {
+        let naming = self.volume_naming()?;
+        let first = self.first_volume_path()?;
+        let (volumes, _) = find_volumes(first.exists().then_some(first)?, naming);
+        Some(volumes.len())
+    }
+
+    /// the name of the first volume of the multipart archive by its
+    /// [naming scheme](Self::volume_naming), or `None` if the archive is not a volume.
+    pub(crate) fn first_volume_path(&self) -> Option<PathBuf> {
+        match self.volume_naming()? {
+            VolumeNaming::New => Archive::new(&self.path).first_part_option(),
+            VolumeNaming::Old => Some(self.path.with_extension("rar")),
+        }
+    }
+
     /// unsets the `damaged` flag so that `Iterator` will not refuse to yield elements.
     ///
     /// Normally, when an error is returned during iteration, the archive remembers this
@@ -229,35 +1136,86 @@ impl<Mode: OpenMode> OpenArchive<Mode, CursorBeforeHeader> {
     pub(crate) fn new(
         filename: &Path,
         password: Option<&[u8]>,
+        callbacks: Callbacks,
+        recover: Option<&mut Option<Self>>,
+    ) -> UnrarResult<Self> {
+        Self::open(filename, password, callbacks, None, recover)
+    }
+
+    /// Opens an archive from `data` held in memory.
+    ///
+    /// Volumes are refused: the UnRAR library would look for subsequent parts
+    /// next to the in-memory file, so they could never be found.
+    pub(crate) fn from_memory(
+        data: Vec<u8>,
+        password: Option<&[u8]>,
+        callbacks: Callbacks,
         recover: Option<&mut Option<Self>>,
     ) -> UnrarResult<Self> {
-        let filename = pathed::construct(filename);
+        let memory =
+            MemoryFile::new(data).map_err(|_| UnrarError::from(Code::EOpen, When::Open))?;
+        let arc = Self::open(&memory.path(), password, callbacks, Some(memory), recover)?;
+        match arc.volume_info() {
+            VolumeInfo::None => Ok(arc),
+            _ => Err(UnrarError::from(Code::EOpen, When::Open)),
+        }
+    }
 
-        let mut data =
-            native::OpenArchiveDataEx::new(filename.as_ptr() as *const _, Mode::VALUE as u32);
-        let handle =
-            NonNull::new(unsafe { native::RAROpenArchiveEx(&mut data as *mut _) } as *mut _);
+    fn open(
+        filename: &Path,
+        password: Option<&[u8]>,
+        callbacks: Callbacks,
+        memory: Option<MemoryFile>,
+        recover: Option<&mut Option<Self>>,
+    ) -> UnrarResult<Self> {
+        let mut callbacks = Box::new(callbacks);
+        let mut comment = callbacks
+            .comment_on_open
+            .then(|| vec![0; COMMENT_BUFFER_SIZE]);
+        let (handle, data) = open_handle(
+            filename,
+            Mode::VALUE as u32,
+            &mut callbacks,
+            comment.as_deref_mut(),
+        )?;
+        // a comment too long for the buffer is left to be read again
+        if let (Some(buffer), 1) = (comment, data.comment_state) {
+            let len = (data.comment_size as usize).saturating_sub(1);
+            callbacks.comment = Some(wide_to_bytes(&buffer[..len]));
+        }
 
-        let arc = handle.and_then(|handle| {
+        let arc = handle.map(|handle| {
             if let Some(pw) = password {
                 let cpw = std::ffi::CString::new(pw).unwrap();
-                unsafe { native::RARSetPassword(handle.as_ptr(), cpw.as_ptr() as *const _) }
+                unsafe { native::RARSetPassword(handle.0.as_ptr(), cpw.as_ptr() as *const _) }
             }
-            Some(OpenArchive {
-                handle: Handle(handle),
+            OpenArchive {
+                handle,
+                memory,
+                callbacks,
+                path: filename.to_path_buf(),
+                password: password.map(<[u8]>::to_vec),
                 damaged: false,
+                finished: false,
                 flags: ArchiveFlags::from_bits(data.flags).unwrap(),
                 extra: CursorBeforeHeader,
                 marker: std::marker::PhantomData,
-            })
+            }
         });
-        let result = Code::from(data.open_result as i32).unwrap();
+        let result = Code::from(data.open_result as i32);
 
         match (arc, result) {
             (Some(arc), Code::Success) => Ok(arc),
             (arc, _) => {
                 recover.and_then(|recover| arc.and_then(|arc| recover.replace(arc)));
-                Err(UnrarError::from(result, When::Open))
+                let mut err = UnrarError::from(result, When::Open);
+                // the library does not tell why it could not open the file
+                if result == Code::EOpen {
+                    if let Err(e) = std::fs::File::open(filename) {
+                        err = err.with_source(e);
+                    }
+                }
+                Err(err)
             }
         }
     }
@@ -267,6 +1225,11 @@ impl<Mode: OpenMode> OpenArchive<Mode, CursorBeforeHeader> {
     /// been read via this method call) will have to be read. Also contains header data
     /// via [`archive.entry()`](OpenArchive::entry).
     ///
+    /// Returns `Ok(None)` at the end of the archive. If the last volume read ends in the
+    /// middle of a header or of an entry's data, e.g. an interrupted download, this fails
+    /// with [`Code::Truncated`] instead of reporting the end of a shorter archive. It cannot
+    /// be told for archives with [encrypted headers](OpenArchive::has_encrypted_headers).
+    ///
     /// # Examples
     ///
     /// Basic usage:
@@ -277,27 +1240,251 @@ impl<Mode: OpenMode> OpenArchive<Mode, CursorBeforeHeader> {
     /// let archive = archive.unwrap().unwrap();
     /// assert_eq!(archive.entry().filename.as_os_str(), "VERSION");
     /// ```
-    pub fn read_header(self) -> UnrarResult<Option<OpenArchive<Mode, CursorBeforeFile>>> {
-        Ok(read_header(&self.handle)?.map(|entry| OpenArchive {
+    pub fn read_header(mut self) -> UnrarResult<Option<OpenArchive<Mode, CursorBeforeFile>>> {
+        Ok(self.read_next_header()?.map(|entry| OpenArchive {
             extra: CursorBeforeFile { header: entry },
             damaged: self.damaged,
+            finished: false,
             handle: self.handle,
+            memory: self.memory,
+            callbacks: self.callbacks,
+            path: self.path,
+            password: self.password,
             flags: self.flags,
             marker: std::marker::PhantomData,
         }))
     }
-}
 
-impl Iterator for OpenArchive<List, CursorBeforeHeader> {
-    type Item = Result<FileHeader, UnrarError>;
+    /// Reads headers like [`read_header`](Self::read_header), skipping over entries whose
+    /// filename does not match the glob `pattern` without unpacking them.
+    ///
+    /// `?` and `*` match any character respectively sequence of characters except `/`,
+    /// `**` also matches across directories and `[...]` any of the enclosed characters
+    /// or ranges (none of them with `[!...]`). Filenames are matched with `/` separating
+    /// their components on all platforms.
+    ///
+    /// Skipping an entry of a [solid](Self::is_solid) archive in [`Process`] mode
+    /// still unpacks it, as the following entries depend on it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let archive = Archive::new("data/directory.rar").open_for_processing().unwrap();
+    /// let archive = archive.read_header_matching("**/*.txt").unwrap().unwrap();
+    /// assert_eq!(archive.entry().filename.as_os_str(), "dir/file.txt");
+    /// let (data, archive) = archive.read().unwrap();
+    /// assert_eq!(data, b"hello\n");
+    /// assert!(archive.read_header_matching("**/*.txt").unwrap().is_none());
+    /// ```
+    pub fn read_header_matching(
+        self,
+        pattern: &str,
+    ) -> UnrarResult<Option<OpenArchive<Mode, CursorBeforeFile>>> {
+        let pattern = Pattern::new(pattern);
+        let mut archive = self;
+        loop {
+            match archive.read_header()? {
+                Some(header) if pattern.matches(&header.entry().filename) => {
+                    return Ok(Some(header))
+                }
+                Some(header) => archive = header.skip()?,
+                None => return Ok(None),
+            }
+        }
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.damaged {
+    /// Skips over entries without unpacking them until the one named `name`, returning the
+    /// archive positioned before its data to extract, read or skip it.
+    ///
+    /// Fails with [`Code::EntryNotFound`] if the archive ends first. Skipping an entry of a
+    /// [solid](Self::is_solid) archive in [`Process`] mode still unpacks it, as the following
+    /// entries depend on it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let archive = Archive::new("data/directory.rar").open_for_processing().unwrap();
+    /// let (data, _) = archive.seek_to("dir/file.txt").unwrap().read().unwrap();
+    /// assert_eq!(data, b"hello\n");
+    /// ```
+    pub fn seek_to<P: AsRef<Path>>(
+        self,
+        name: P,
+    ) -> UnrarResult<OpenArchive<Mode, CursorBeforeFile>> {
+        let name = name.as_ref();
+        let mut archive = self;
+        while let Some(header) = archive.read_header()? {
+            if header.entry().filename == name {
+                return Ok(header);
+            }
+            archive = header.skip()?;
+        }
+        Err(UnrarError::from(Code::EntryNotFound, When::Read).with_file(name))
+    }
+
+    /// Returns an iterator over the entries whose filename matches the glob `pattern`,
+    /// see [`read_header_matching`](Self::read_header_matching) for the syntax.
+    /// Errors are passed through.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let archive = Archive::new("data/directory.rar").open_for_listing().unwrap();
+    /// let dirs: Vec<_> = archive
+    ///     .entries_matching("[d-e]*")
+    ///     .map(|entry| entry.unwrap().filename)
+    ///     .collect();
+    /// assert_eq!(dirs, ["dir", "empty"].map(std::path::PathBuf::from));
+    /// ```
+    pub fn entries_matching(self, pattern: &str) -> impl Iterator<Item = UnrarResult<FileHeader>>
+    where
+        Self: Iterator<Item = UnrarResult<FileHeader>>,
+    {
+        let pattern = Pattern::new(pattern);
+        self.filter(move |entry| {
+            entry
+                .as_ref()
+                .map_or(true, |entry| pattern.matches(&entry.filename))
+        })
+    }
+
+    /// Returns an iterator over the entries whose [`unpacked_size`](FileHeader::unpacked_size)
+    /// is larger than `bytes`. Errors are passed through.
+    ///
+    /// Entries left out are skipped with `RAR_SKIP` like all entries when listing, so they
+    /// are never unpacked. To unpack only the entries found, process the archive and
+    /// [`skip`](OpenArchive::skip) the others, which still unpacks them in a
+    /// [solid](Self::is_solid) archive, as the following entries depend on them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let archive = Archive::new("data/filter.rar").open_for_listing().unwrap();
+    /// let mut entries = archive.entries_larger_than(100);
+    /// assert_eq!(entries.next().unwrap().unwrap().filename.as_os_str(), "newest.txt");
+    /// assert!(entries.next().is_none());
+    /// ```
+    pub fn entries_larger_than(self, bytes: u64) -> impl Iterator<Item = UnrarResult<FileHeader>>
+    where
+        Self: Iterator<Item = UnrarResult<FileHeader>>,
+    {
+        self.filter(move |entry| {
+            entry
+                .as_ref()
+                .map_or(true, |entry| entry.unpacked_size > bytes)
+        })
+    }
+
+    /// Returns an iterator over the entries whose [`unpacked_size`](FileHeader::unpacked_size)
+    /// is smaller than `bytes`, see [`entries_larger_than`](Self::entries_larger_than).
+    pub fn entries_smaller_than(self, bytes: u64) -> impl Iterator<Item = UnrarResult<FileHeader>>
+    where
+        Self: Iterator<Item = UnrarResult<FileHeader>>,
+    {
+        self.filter(move |entry| {
+            entry
+                .as_ref()
+                .map_or(true, |entry| entry.unpacked_size < bytes)
+        })
+    }
+
+    /// Returns an iterator over the entries [modified](FileHeader::modified_time) within
+    /// `range`, e.g. `start..end`. Entries without a modification time are left out.
+    /// Errors are passed through.
+    ///
+    /// Listing reads the headers only, so the entries left out are not unpacked.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let start = UNIX_EPOCH + Duration::from_secs(1_650_000_000);
+    /// let end = UNIX_EPOCH + Duration::from_secs(1_750_000_000);
+    /// let archive = Archive::new("data/filter.rar").open_for_listing().unwrap();
+    /// let mut entries = archive.entries_modified_within(start..end);
+    /// assert_eq!(entries.next().unwrap().unwrap().filename.as_os_str(), "new.txt");
+    /// assert!(entries.next().is_none());
+    /// ```
+    pub fn entries_modified_within<R>(
+        self,
+        range: R,
+    ) -> impl Iterator<Item = UnrarResult<FileHeader>>
+    where
+        Self: Iterator<Item = UnrarResult<FileHeader>>,
+        R: RangeBounds<SystemTime>,
+    {
+        self.filter(move |entry| {
+            entry.as_ref().map_or(true, |entry| {
+                entry
+                    .modified_time()
+                    .is_some_and(|time| range.contains(&time))
+            })
+        })
+    }
+
+    /// Returns an iterator over the entries modified after `time`, see
+    /// [`entries_modified_within`](Self::entries_modified_within).
+    pub fn entries_modified_after(
+        self,
+        time: SystemTime,
+    ) -> impl Iterator<Item = UnrarResult<FileHeader>>
+    where
+        Self: Iterator<Item = UnrarResult<FileHeader>>,
+    {
+        self.entries_modified_within((Bound::Excluded(time), Bound::Unbounded))
+    }
+
+    /// Returns an iterator over the entries modified before `time`, see
+    /// [`entries_modified_within`](Self::entries_modified_within).
+    pub fn entries_modified_before(
+        self,
+        time: SystemTime,
+    ) -> impl Iterator<Item = UnrarResult<FileHeader>>
+    where
+        Self: Iterator<Item = UnrarResult<FileHeader>>,
+    {
+        self.entries_modified_within(..time)
+    }
+}
+
+impl<Mode: OpenMode> OpenArchive<Mode, CursorBeforeHeader> {
+    /// reads the next header, failing with [`Code::Truncated`] instead of reporting the end
+    /// of the archive if the last volume read ends early.
+    fn read_next_header(&mut self) -> UnrarResult<Option<FileHeader>> {
+        let entry = read_header(&self.handle, &mut self.callbacks)?;
+        match &entry {
+            Some(entry) => self.callbacks.last_volume = Some(entry.volume.clone()),
+            None if !self.has_encrypted_headers() => {
+                let volume = self.callbacks.last_volume.as_deref().unwrap_or(&self.path);
+                if blocks::is_truncated(volume) {
+                    return Err(UnrarError::from(Code::Truncated, When::Read));
+                }
+            }
+            None => {}
+        }
+        Ok(entry)
+    }
+
+    /// reads the next header and skips over its entry, for iterating in the list modes.
+    fn next_entry(&mut self) -> Option<UnrarResult<FileHeader>> {
+        if self.damaged || self.finished {
             return None;
         }
-        match read_header(&self.handle) {
+        match self.read_next_header() {
             Ok(Some(header)) => {
-                match Internal::<Skip>::process_file_raw(&self.handle, None, None) {
+                match Internal::<Skip>::process_file_raw(
+                    &self.handle,
+                    &mut self.callbacks,
+                    Some(&header),
+                    None,
+                    None,
+                ) {
                     Ok(_) => Some(Ok(header)),
                     Err(s) => {
                         self.damaged = true;
@@ -305,38 +1492,219 @@ impl Iterator for OpenArchive<List, CursorBeforeHeader> {
                     }
                 }
             }
-            Ok(None) => None,
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
             Err(s) => {
                 self.damaged = true;
                 Some(Err(s))
             }
         }
     }
+
+    /// the UnRAR library does not tell the number of entries up front
+    fn entries_hint(&self) -> (usize, Option<usize>) {
+        match self.damaged || self.finished {
+            true => (0, Some(0)),
+            false => (0, None),
+        }
+    }
 }
 
+/// Yields the entries until the end of the archive or the first error, after
+/// which it only returns `None` (unless [healed](OpenArchive::force_heal)).
+impl Iterator for OpenArchive<List, CursorBeforeHeader> {
+    type Item = Result<FileHeader, UnrarError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_entry()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries_hint()
+    }
+}
+impl FusedIterator for OpenArchive<List, CursorBeforeHeader> {}
+
+/// Yields the entries until the end of the archive or the first error, after
+/// which it only returns `None` (unless [healed](OpenArchive::force_heal)).
 impl Iterator for OpenArchive<ListSplit, CursorBeforeHeader> {
     type Item = Result<FileHeader, UnrarError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.damaged {
-            return None;
-        }
-        match read_header(&self.handle) {
-            Ok(Some(header)) => {
-                match Internal::<Skip>::process_file_raw(&self.handle, None, None) {
-                    Ok(_) => Some(Ok(header)),
-                    Err(s) => {
-                        self.damaged = true;
-                        Some(Err(s))
-                    }
+        self.next_entry()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries_hint()
+    }
+}
+impl FusedIterator for OpenArchive<ListSplit, CursorBeforeHeader> {}
+
+impl OpenArchive<Process, CursorBeforeHeader> {
+    /// Extracts all remaining entries into `base` like
+    /// [`extract_with_base`](OpenArchive::extract_with_base), creating directories as needed,
+    /// and returns the entries, the way [`Archive::list`] lists them, along with what
+    /// extracting them did.
+    ///
+    /// The [overwrite policy](crate::Archive::with_overwrite) and
+    /// [sanitization](crate::Archive::with_sanitize) apply, so entries may be
+    /// [skipped](ExtractOutcome::Skipped) or [renamed](ExtractOutcome::Renamed).
+    /// Fails with the first error encountered.
+    ///
+    /// Directory entries are created even if they are empty. Their modification time is
+    /// restored once all entries are extracted, as extracting their contents changes it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::{Archive, ExtractOutcome};
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let archive = Archive::new("data/directory.rar").open_for_processing().unwrap();
+    /// let entries = archive.extract_all_to(dir.path()).unwrap();
+    /// assert_eq!(entries.len(), 3);
+    /// assert!(entries.iter().all(|(_, outcome)| *outcome == ExtractOutcome::Written));
+    /// assert_eq!(std::fs::read(dir.path().join("dir/file.txt")).unwrap(), b"hello\n");
+    /// ```
+    pub fn extract_all_to<P: AsRef<Path>>(
+        self,
+        base: P,
+    ) -> UnrarResult<Vec<(FileHeader, ExtractOutcome)>> {
+        self.with_header_hook(base, |_| EntryAction::Extract)
+    }
+
+    /// Extracts the remaining entries into `base` like [`extract_all_to`](Self::extract_all_to),
+    /// letting `hook` decide for each header, as soon as it is read, whether to extract the
+    /// entry, skip it or stop.
+    ///
+    /// This drives the read-header then extract-or-skip loop for policies like size caps or
+    /// name filters, which are composed in the hook. Entries skipped by the hook are returned
+    /// as [skipped](ExtractOutcome::Skipped). [`EntryAction::Abort`] fails with
+    /// [`Code::Cancelled`] for the entry's path, leaving the entries before it extracted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::{Archive, EntryAction, ExtractOutcome};
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let archive = Archive::new("data/version.rar").open_for_processing().unwrap();
+    /// let entries = archive
+    ///     .with_header_hook(dir.path(), |entry| match entry.unpacked_size {
+    ///         0..=1024 => EntryAction::Extract,
+    ///         _ => EntryAction::Skip,
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(entries[0].1, ExtractOutcome::Written);
+    /// assert!(dir.path().join("VERSION").exists());
+    /// ```
+    pub fn with_header_hook<P, F>(
+        self,
+        base: P,
+        mut hook: F,
+    ) -> UnrarResult<Vec<(FileHeader, ExtractOutcome)>>
+    where
+        P: AsRef<Path>,
+        F: FnMut(&FileHeader) -> EntryAction,
+    {
+        let base = base.as_ref();
+        let mut extracted = Vec::new();
+        let mut dirs = Vec::new();
+        let mut archive = self;
+        while let Some(header) = archive.read_header()? {
+            let target = match hook(header.entry()) {
+                EntryAction::Extract => header.dir_target(Some(base))?,
+                EntryAction::Skip => None,
+                EntryAction::Abort => {
+                    return Err(UnrarError::from(Code::Cancelled, When::Process)
+                        .with_file(&header.entry().filename))
                 }
+            };
+            let (entry, outcome, rest) = match target {
+                Some(((path, file), outcome)) => {
+                    dirs.extend(header.create_dir(base)?);
+                    let ((), result) =
+                        header.process_file_with::<Extract>(path.as_deref(), file.as_deref(), ());
+                    let (entry, rest) = result?;
+                    (entry, outcome, rest)
+                }
+                None => {
+                    let entry = header.entry().clone();
+                    (entry, ExtractOutcome::Skipped, header.skip()?)
+                }
+            };
+            extracted.push((entry, outcome));
+            archive = rest;
+        }
+        restore_dir_times(&dirs);
+        Ok(extracted)
+    }
+
+    /// Reads the entries named in `names` into memory in a single pass over the archive,
+    /// skipping the others, and returns their data by name along with the names not found in
+    /// the archive. Stops reading as soon as all of them were found.
+    ///
+    /// For solid archives, where skipping still has to decompress the data, this is much
+    /// faster than one [`Archive::extract_file`](crate::Archive::extract_file) per name.
+    /// Fails with the first error encountered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// # use std::path::PathBuf;
+    /// # use unrar::Archive;
+    /// let names = HashSet::from([PathBuf::from("dir/file.txt"), PathBuf::from("missing")]);
+    /// let archive = Archive::new("data/directory.rar").open_for_processing().unwrap();
+    /// let (data, missing) = archive.extract_many(&names).unwrap();
+    /// assert_eq!(data[&PathBuf::from("dir/file.txt")], b"hello\n");
+    /// assert_eq!(missing, HashSet::from([PathBuf::from("missing")]));
+    /// ```
+    pub fn extract_many(self, names: &HashSet<PathBuf>) -> UnrarResult<ExtractedMany> {
+        let mut extracted = HashMap::new();
+        let mut archive = self;
+        while extracted.len() < names.len() {
+            let Some(header) = archive.read_header()? else {
+                break;
+            };
+            let name = &header.entry().filename;
+            if !names.contains(name) || extracted.contains_key(name) {
+                archive = header.skip()?;
+                continue;
             }
-            Ok(None) => None,
-            Err(s) => {
-                self.damaged = true;
-                Some(Err(s))
-            }
+            let name = name.clone();
+            let (data, rest) = header.read()?;
+            extracted.insert(name, data);
+            archive = rest;
         }
+        let missing = names
+            .iter()
+            .filter(|name| !extracted.contains_key(*name))
+            .cloned()
+            .collect();
+        Ok((extracted, missing))
+    }
+
+    /// Turns the archive into an iterator over the remaining entries and their data, reading
+    /// one entry into memory each time the iterator is advanced, see [`ExtractingIter`].
+    ///
+    /// Unlike [`extract_many`](Self::extract_many), only a single entry is held in memory at
+    /// a time, so even huge archives can be processed entry by entry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let archive = Archive::new("data/directory.rar").open_for_processing().unwrap();
+    /// for entry in archive.into_extracting_iter() {
+    ///     let (entry, data) = entry.unwrap();
+    ///     if entry.is_file() {
+    ///         assert_eq!(data.len() as u64, entry.unpacked_size);
+    ///     }
+    /// }
+    /// ```
+    pub fn into_extracting_iter(self) -> ExtractingIter {
+        ExtractingIter::new(self)
     }
 }
 
@@ -351,11 +1719,45 @@ impl<M: OpenMode> OpenArchive<M, CursorBeforeFile> {
         self.process_file::<Skip>(None, None)
     }
 
+    /// Replaces the password used to process this entry and the ones after it, without
+    /// reopening the archive. This is what the `RARSetPassword` call of the UnRAR library does,
+    /// which takes precedence over the [password callback](crate::Archive::with_password_callback).
+    ///
+    /// The password can only be replaced before an entry is processed: when processing fails
+    /// with [`Code::BadPassword`], the library has already moved past the entry's data and
+    /// cannot go back. Reopen the archive with the new password to retry it, e.g. with
+    /// [`Archive::extract_file`](crate::Archive::extract_file). Archives with
+    /// [encrypted headers](OpenArchive::has_encrypted_headers) need the password to be
+    /// read at all, so it has to be right when opening them.
+    ///
+    /// Fails with [`Code::ENul`] if `password` contains nul bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let archive = Archive::with_password("data/crypted.rar", "wrong").open_for_processing();
+    /// let mut archive = archive.unwrap().read_header().unwrap().unwrap();
+    /// // e.g. after asking the user again
+    /// archive.retry_password(b"unrar").unwrap();
+    /// let (data, _) = archive.read().unwrap();
+    /// assert_eq!(data, b"target\nCargo.lock\n");
+    /// ```
+    pub fn retry_password(&mut self, password: &[u8]) -> UnrarResult<()> {
+        let cpw = std::ffi::CString::new(password).map_err(NulError::from)?;
+        unsafe { native::RARSetPassword(self.handle.0.as_ptr(), cpw.as_ptr() as *const _) }
+        self.password = Some(password.to_vec());
+        Ok(())
+    }
+
     fn process_file<PM: ProcessMode>(
         self,
         path: Option<&pathed::RarStr>,
         file: Option<&pathed::RarStr>,
-    ) -> UnrarResult<OpenArchive<M, CursorBeforeHeader>> {
+    ) -> UnrarResult<OpenArchive<M, CursorBeforeHeader>>
+    where
+        PM::Output: Default,
+    {
         Ok(self.process_file_x::<PM>(path, file)?.1)
     }
 
@@ -363,18 +1765,62 @@ impl<M: OpenMode> OpenArchive<M, CursorBeforeFile> {
         self,
         path: Option<&pathed::RarStr>,
         file: Option<&pathed::RarStr>,
-    ) -> UnrarResult<(PM::Output, OpenArchive<M, CursorBeforeHeader>)> {
-        let result = Ok((
-            Internal::<PM>::process_file_raw(&self.handle, path, file)?,
-            OpenArchive {
-                extra: CursorBeforeHeader,
-                damaged: self.damaged,
-                handle: self.handle,
-                flags: self.flags,
-                marker: std::marker::PhantomData,
-            },
-        ));
-        result
+    ) -> UnrarResult<(PM::Output, OpenArchive<M, CursorBeforeHeader>)>
+    where
+        PM::Output: Default,
+    {
+        let (data, result) = self.process_file_with::<PM>(path, file, Default::default());
+        Ok((data, result?.1))
+    }
+
+    /// processes the file with `data` as the initial state of the process mode's output.
+    /// The output is returned regardless of whether processing succeeded, the header
+    /// of the processed file only on success.
+    fn process_file_with<PM: ProcessMode>(
+        self,
+        path: Option<&pathed::RarStr>,
+        file: Option<&pathed::RarStr>,
+        data: PM::Output,
+    ) -> (PM::Output, UnrarResult<Processed<M>>) {
+        let (data, header, result, archive) = self.process_file_keeping::<PM>(path, file, data);
+        (data, result.map(|_| (header, archive)))
+    }
+
+    /// like [`process_file_with`](Self::process_file_with), but keeps the archive even if
+    /// processing failed. Only continue with it if the library got past the file's data,
+    /// as it does after a [`Code::BadData`].
+    fn process_file_keeping<PM: ProcessMode>(
+        mut self,
+        path: Option<&pathed::RarStr>,
+        file: Option<&pathed::RarStr>,
+        data: PM::Output,
+    ) -> (
+        PM::Output,
+        FileHeader,
+        UnrarResult<()>,
+        OpenArchive<M, CursorBeforeHeader>,
+    ) {
+        let (data, result) = Internal::<PM>::process_file_with(
+            &self.handle,
+            &mut self.callbacks,
+            Some(&self.extra.header),
+            path,
+            file,
+            data,
+        );
+        let archive = OpenArchive {
+            extra: CursorBeforeHeader,
+            damaged: self.damaged,
+            finished: false,
+            handle: self.handle,
+            memory: self.memory,
+            callbacks: self.callbacks,
+            path: self.path,
+            password: self.password,
+            flags: self.flags,
+            marker: std::marker::PhantomData,
+        };
+        (data, self.extra.header, result, archive)
     }
 }
 
@@ -382,16 +1828,201 @@ impl OpenArchive<Process, CursorBeforeFile> {
     /// Reads the underlying file into a `Vec<u8>`
     /// Returns the data as well as the owned Archive that can be processed further.
     pub fn read(self) -> UnrarResult<(Vec<u8>, OpenArchive<Process, CursorBeforeHeader>)> {
-        Ok(self.process_file_x::<ReadToVec>(None, None)?)
+        let (_, data, rest) = self.read_bytes()?;
+        Ok((data, rest))
+    }
+
+    /// Reads the underlying file into a `Vec<u8>`, preallocated to the entry's `unpacked_size`.
+    /// Returns the header of the file that was read and its data as well as the owned Archive
+    /// that can be processed further.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let archive = Archive::new("data/version.rar").open_for_processing().unwrap();
+    /// let (entry, data, _rest) = archive.read_header().unwrap().unwrap().read_bytes().unwrap();
+    /// assert_eq!(entry.filename.as_os_str(), "VERSION");
+    /// assert_eq!(data, b"unrar-0.4.0");
+    /// ```
+    pub fn read_bytes(
+        self,
+    ) -> UnrarResult<(
+        FileHeader,
+        Vec<u8>,
+        OpenArchive<Process, CursorBeforeHeader>,
+    )> {
+        let data = self.data_buffer();
+        let allow_partial = self.callbacks.allow_partial;
+        let (data, result) = self.process_file_with::<ReadToVec>(None, None, data);
+        match result {
+            Ok((header, rest)) => Ok((header, data, rest)),
+            Err(e) if allow_partial && e.code == Code::BadData => Err(e.with_partial_data(data)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Reads the underlying file into `buf`, reusing its allocation, and returns the owned
+    /// Archive that can be processed further.
+    ///
+    /// `buf` is cleared first, so whatever it held is overwritten and it ends up holding
+    /// just the entry's data. Nothing is allocated if its capacity already covers the entry's
+    /// `unpacked_size`, so one buffer can serve many entries in a loop. If the entry is
+    /// [corrupted](Code::BadData), `buf` holds what was unpacked of it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let mut buf = Vec::with_capacity(1024);
+    /// let mut archive = Archive::new("data/directory.rar").open_for_processing().unwrap();
+    /// while let Some(header) = archive.read_header().unwrap() {
+    ///     let size = header.entry().unpacked_size;
+    ///     archive = header.read_into(&mut buf).unwrap();
+    ///     assert_eq!(buf.len() as u64, size);
+    /// }
+    /// assert_eq!(buf.capacity(), 1024);
+    /// ```
+    pub fn read_into(
+        self,
+        buf: &mut Vec<u8>,
+    ) -> UnrarResult<OpenArchive<Process, CursorBeforeHeader>> {
+        buf.clear();
+        self.reserve_data(buf);
+        let (data, result) = self.process_file_with::<ReadToVec>(None, None, std::mem::take(buf));
+        *buf = data;
+        Ok(result?.1)
+    }
+
+    /// reads the entry like [`read_bytes`](Self::read_bytes), returning the archive along with
+    /// the result if the library can go on with the next header, i.e. after success or
+    /// [`Code::BadData`].
+    pub(crate) fn read_continuing(
+        self,
+    ) -> (
+        FileHeader,
+        UnrarResult<Vec<u8>>,
+        Option<OpenArchive<Process, CursorBeforeHeader>>,
+    ) {
+        let data = self.data_buffer();
+        let allow_partial = self.callbacks.allow_partial;
+        let (data, header, result, archive) =
+            self.process_file_keeping::<ReadToVec>(None, None, data);
+        match result {
+            Ok(()) => (header, Ok(data), Some(archive)),
+            Err(e) if e.code == Code::BadData => {
+                let e = match allow_partial {
+                    true => e.with_partial_data(data),
+                    false => e,
+                };
+                (header, Err(e), Some(archive))
+            }
+            Err(e) => (header, Err(e), None),
+        }
+    }
+
+    /// an empty buffer for the entry's data, preallocated to its `unpacked_size`.
+    fn data_buffer(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        self.reserve_data(&mut data);
+        data
+    }
+
+    /// reserves room for the entry's `unpacked_size` in `data`.
+    fn reserve_data(&self, data: &mut Vec<u8>) {
+        // the header might not tell the truth, so fall back
+        // to growing the buffer if the allocation fails.
+        if let Ok(size) = usize::try_from(self.entry().unpacked_size) {
+            let _ = data.try_reserve_exact(size);
+        }
+    }
+
+    /// Streams the underlying file into `writer`.
+    /// Returns the writer as well as the owned Archive that can be processed further.
+    ///
+    /// If `writer` fails, processing is aborted and an error with [`Code::EWrite`]
+    /// is returned.
+    ///
+    /// Data is written as it is unpacked, so if the entry turns out to be corrupted
+    /// ([`Code::BadData`]), `writer` already received all of it. Pass `&mut writer`
+    /// to keep it in that case.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// # use std::io::Cursor;
+    /// let archive = Archive::new("data/version.rar").open_for_processing().unwrap();
+    /// let archive = archive.read_header().unwrap().unwrap();
+    /// let (cursor, _rest) = archive.extract_to_writer(Cursor::new(Vec::new())).unwrap();
+    /// assert_eq!(cursor.into_inner(), b"unrar-0.4.0");
+    /// ```
+    pub fn extract_to_writer<W: Write>(
+        self,
+        writer: W,
+    ) -> UnrarResult<(W, OpenArchive<Process, CursorBeforeHeader>)> {
+        let sink = Sink {
+            writer,
+            error: None,
+        };
+        let filename = self.entry().filename.clone();
+        let (sink, result) = self.process_file_with::<ToWriter<W>>(None, None, sink);
+        match sink.error {
+            Some(error) => Err(UnrarError::from(Code::EWrite, When::Process)
+                .with_file(&filename)
+                .with_source(error)),
+            None => Ok((sink.writer, result?.1)),
+        }
+    }
+
+    /// Reads the underlying file through [`std::io::Read`], e.g. to pass it to a parser
+    /// expecting a reader. Errors unpacking the file are returned by
+    /// [`read`](std::io::Read::read) once all data unpacked before them has been read.
+    ///
+    /// Use [`EntryReader::finish`] to process further files.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// use std::io::Read;
+    ///
+    /// let archive = Archive::new("data/version.rar").open_for_processing().unwrap();
+    /// let mut reader = archive.read_header().unwrap().unwrap().entry_reader();
+    /// let mut version = String::new();
+    /// reader.read_to_string(&mut version).unwrap();
+    /// assert_eq!(version, "unrar-0.4.0");
+    /// assert!(reader.finish().unwrap().read_header().unwrap().is_none());
+    /// ```
+    pub fn entry_reader(self) -> EntryReader {
+        EntryReader::new(self)
     }
 
     /// Test the file without extracting it
     pub fn test(self) -> UnrarResult<OpenArchive<Process, CursorBeforeHeader>> {
-        Ok(self.process_file::<Test>(None, None)?)
+        self.process_file::<Test>(None, None)
+    }
+
+    /// tests the file like [`test`](Self::test), returning the archive along with the
+    /// result if the library can go on with the next header, which is after a
+    /// [`Code::BadData`] or on success.
+    pub(crate) fn test_continuing(self) -> Continued {
+        let ((), header, result, archive) = self.process_file_keeping::<Test>(None, None, ());
+        match result {
+            Err(e) if e.code != Code::BadData => (header, Err(e), None),
+            result => (header, result, Some(archive)),
+        }
     }
 
     /// Extracts the file into the current working directory
     /// Returns the OpenArchive for further processing
+    ///
+    /// Like [`extract_with_base`](Self::extract_with_base), this rejects entries
+    /// escaping the directory with [`Code::UnsafePath`].
+    ///
+    /// Like all methods extracting to disk, this restores the modification time
+    /// and the attributes (or [Unix permissions](FileHeader::unix_mode)) stored in the archive,
+    /// and follows the [`OverwritePolicy`] if the file already exists.
     pub fn extract(self) -> UnrarResult<OpenArchive<Process, CursorBeforeHeader>> {
         self.dir_extract(None)
     }
@@ -399,9 +2030,13 @@ impl OpenArchive<Process, CursorBeforeFile> {
     /// Extracts the file into the specified directory.  
     /// Returns the OpenArchive for further processing
     ///
-    /// # Panics
+    /// Fails with [`Code::UnsafePath`] without extracting if the entry's path is absolute
+    /// or climbs out of `base` with `..`, unless [disabled](crate::Archive::with_sanitize).
     ///
-    /// This function will panic if `base` contains nul characters.
+    /// Fails with [`Code::ENul`] if `base` contains nul characters.
+    ///
+    /// On Windows, `base` is passed to the library with the `\\?\` prefix, so that
+    /// entries nested deeper than `MAX_PATH` can still be extracted.
     pub fn extract_with_base<P: AsRef<Path>>(
         self,
         base: P,
@@ -412,15 +2047,99 @@ impl OpenArchive<Process, CursorBeforeFile> {
     /// Extracts the file into the specified file.
     /// Returns the OpenArchive for further processing
     ///
-    /// # Panics
+    /// The entry is extracted to a hidden file next to `file` first, which is only renamed
+    /// to `file` once extracting succeeded, so a failure never leaves a partial file behind
+    /// or replaces an existing one. The partial file is kept if
+    /// [`allow_partial`](crate::Archive::with_allow_partial) is set and the entry is
+    /// [corrupted](Code::BadData). Directories are created in place.
     ///
-    /// This function will panic if `dest` contains nul characters.
+    /// Fails with [`Code::ENul`] if `dest` contains nul characters.
     pub fn extract_to<P: AsRef<Path>>(
         self,
         file: P,
     ) -> UnrarResult<OpenArchive<Process, CursorBeforeHeader>> {
-        let dest = pathed::construct(file.as_ref());
-        self.process_file::<Extract>(None, Some(&dest))
+        let file = match self.overwrite_target(file.as_ref()) {
+            Target::Skip => return self.skip(),
+            Target::Rename(dest) => dest,
+            Target::Keep => file.as_ref().to_path_buf(),
+        };
+        if self.entry().is_directory() {
+            let dest = pathed::construct_dest(&file).map_err(process_nul_error)?;
+            return self.process_file::<Extract>(None, Some(&dest));
+        }
+        let temp = temporary_sibling(&file);
+        let dest = pathed::construct_dest(&temp).map_err(process_nul_error)?;
+        let filename = self.entry().filename.clone();
+        let allow_partial = self.callbacks.allow_partial;
+        let result = self.process_file::<Extract>(None, Some(&dest));
+        let keep = match &result {
+            Ok(_) => true,
+            Err(e) => allow_partial && e.code == Code::BadData,
+        };
+        if !keep || temp.symlink_metadata().is_err() {
+            let _ = std::fs::remove_file(&temp);
+        } else if let Err(e) = std::fs::rename(&temp, &file) {
+            let _ = std::fs::remove_file(&temp);
+            return Err(UnrarError::from(Code::ECreate, When::Process)
+                .with_file(&filename)
+                .with_source(e));
+        }
+        result
+    }
+
+    /// Extracts the file into the specified directory like [`extract_with_base`](Self::extract_with_base),
+    /// additionally computing the hash of the unpacked data and comparing it to the stored
+    /// [CRC32](FileHeader::crc32) or [BLAKE2sp](FileHeader::blake2sp) hash,
+    /// failing with [`Code::BadData`] on mismatch.
+    ///
+    /// The UnRAR library already checks the hash on its own, this guards against
+    /// corruption it does not notice. Fails with [`Code::Unverifiable`] without extracting
+    /// if the entry's hash cannot be computed, i.e. it has no hash at all, is hashed with
+    /// BLAKE2sp but the `blake2` feature is disabled, or is [split](FileHeader::is_split),
+    /// in which case the stored hash only covers part of the data.
+    /// Directories are extracted without verification.
+    ///
+    /// Fails with [`Code::Unsupported`] if the UnRAR library is too old to report
+    /// hashes, see [`supports_rar5`](crate::supports_rar5).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use unrar::Archive;
+    /// let mut archive = Archive::new("data/version.rar").open_for_processing().unwrap();
+    /// while let Some(header) = archive.read_header().unwrap() {
+    ///     archive = header.extract_and_verify("out").unwrap();
+    /// }
+    /// ```
+    pub fn extract_and_verify<P: AsRef<Path>>(
+        self,
+        base: P,
+    ) -> UnrarResult<OpenArchive<Process, CursorBeforeHeader>> {
+        dll::require_rar5(When::Process)?;
+        let entry = self.entry();
+        if entry.is_directory() {
+            return self.extract_with_base(base);
+        }
+        let expected = entry.hash;
+        let hasher = match Hasher::new(&expected) {
+            Some(hasher) if !entry.is_split() => hasher,
+            _ => {
+                return Err(
+                    UnrarError::from(Code::Unverifiable, When::Process).with_file(&entry.filename)
+                )
+            }
+        };
+        let Some(((path, file), _)) = self.dir_target(Some(base.as_ref()))? else {
+            return self.skip();
+        };
+        let data = Verified { data: (), hasher };
+        let (verified, result) =
+            self.process_file_with::<Verify<Extract>>(path.as_deref(), file.as_deref(), data);
+        let (header, rest) = result?;
+        if verified.hasher.finish() != expected {
+            return Err(UnrarError::from(Code::BadData, When::Process).with_file(&header.filename));
+        }
+        Ok(rest)
     }
 
     /// extracting into a directory if the filename has unicode characters
@@ -429,13 +2148,311 @@ impl OpenArchive<Process, CursorBeforeFile> {
         self,
         base: Option<&Path>,
     ) -> UnrarResult<OpenArchive<Process, CursorBeforeHeader>> {
-        let (path, file) = pathed::preprocess_extract(base, &self.entry().filename);
-        self.process_file::<Extract>(path.as_deref(), file.as_deref())
+        match self.dir_target(base)? {
+            Some(((path, file), _)) => {
+                self.process_file::<Extract>(path.as_deref(), file.as_deref())
+            }
+            None => self.skip(),
+        }
+    }
+
+    /// the destination path and name to pass to the UnRAR library to extract the entry
+    /// into `base` along with the outcome of extracting it there, or `None` if it is to be
+    /// skipped according to the [`OverwritePolicy`]. Entries left without a path by
+    /// [stripping](crate::Archive::with_strip_components) are skipped as well.
+    fn dir_target(
+        &self,
+        base: Option<&Path>,
+    ) -> UnrarResult<Option<(ExtractTarget, ExtractOutcome)>> {
+        let Some(filename) = self.stripped_filename()? else {
+            return Ok(None);
+        };
+        let dest = base.unwrap_or(".".as_ref()).join(&filename);
+        let target = match self.overwrite_target(&dest) {
+            Target::Skip => return Ok(None),
+            Target::Rename(dest) => pathed::construct_dest(&dest)
+                .map(|file| ((None, Some(file)), ExtractOutcome::Renamed(dest))),
+            // the library would join the full filename to `base` on its own
+            Target::Keep if self.callbacks.strip_components > 0 => pathed::construct_dest(&dest)
+                .map(|file| ((None, Some(file)), ExtractOutcome::Written)),
+            Target::Keep => pathed::preprocess_extract(base, &filename)
+                .map(|target| (target, ExtractOutcome::Written)),
+        };
+        target.map(Some).map_err(process_nul_error)
     }
+
+    /// the path to extract the entry to relative to the directory it is extracted into,
+    /// i.e. its filename without the first [`strip_components`](crate::Archive::with_strip_components),
+    /// or `None` if nothing is left of it.
+    ///
+    /// Fails with [`Code::UnsafePath`] if the path is not [contained](is_contained),
+    /// unless sanitization is disabled.
+    fn stripped_filename(&self) -> UnrarResult<Option<PathBuf>> {
+        let filename = &self.entry().filename;
+        let stripped = match self.callbacks.strip_components {
+            0 => filename.clone(),
+            n => filename.components().skip(n).collect(),
+        };
+        if stripped.as_os_str().is_empty() {
+            return Ok(None);
+        }
+        if !self.callbacks.unsanitized && !is_contained(&stripped) {
+            return Err(UnrarError::from(Code::UnsafePath, When::Process).with_file(filename));
+        }
+        Ok(Some(stripped))
+    }
+
+    /// creates the directory of a directory entry in `base` along with its parents, so that it
+    /// exists even if it is empty. Returns its path and modification time, which is to be
+    /// restored with [`restore_dir_times`] once its contents are extracted.
+    fn create_dir(&self, base: &Path) -> UnrarResult<Option<(PathBuf, SystemTime)>> {
+        let entry = self.entry();
+        if !entry.is_directory() {
+            return Ok(None);
+        }
+        let Some(filename) = self.stripped_filename()? else {
+            return Ok(None);
+        };
+        let dir = base.join(filename);
+        std::fs::create_dir_all(&dir).map_err(|e| {
+            UnrarError::from(Code::ECreate, When::Process)
+                .with_file(&entry.filename)
+                .with_source(e)
+        })?;
+        Ok(entry.modified_time().map(|mtime| (dir, mtime)))
+    }
+
+    /// applies the [`OverwritePolicy`] to extracting the entry to `dest`.
+    fn overwrite_target(&self, dest: &Path) -> Target {
+        if self.entry().is_directory() || dest.symlink_metadata().is_err() {
+            return Target::Keep;
+        }
+        match self.callbacks.overwrite {
+            OverwritePolicy::Overwrite => Target::Keep,
+            OverwritePolicy::Skip => Target::Skip,
+            OverwritePolicy::Rename => Target::Rename(renamed(dest)),
+        }
+    }
+}
+
+impl OpenArchive<Process, CursorBeforeFile> {
+    /// extracts the entry into `base` like [`extract_with_base`](Self::extract_with_base),
+    /// returning the archive along with the result if the library can go on with the next
+    /// header and either `continue_on_error` is set or extracting succeeded. Extracting
+    /// goes on after [`Code::BadData`] only.
+    ///
+    /// Directory entries are created in `base` first, adding their path and modification
+    /// time to `dirs` for [`restore_dir_times`].
+    pub(crate) fn extract_continuing(
+        self,
+        base: &Path,
+        dirs: &mut Vec<(PathBuf, SystemTime)>,
+    ) -> Continued<ExtractOutcome> {
+        let target = match self.dir_target(Some(base)) {
+            Ok(Some(target)) => self.create_dir(base).map(|dir| {
+                dirs.extend(dir);
+                Some(target)
+            }),
+            other => other,
+        };
+        let ((path, file), outcome) = match target {
+            Ok(Some(target)) => target,
+            Ok(None) => {
+                let header = self.entry().clone();
+                return match self.skip() {
+                    Ok(rest) => (header, Ok(ExtractOutcome::Skipped), Some(rest)),
+                    Err(e) => (header, Err(e), None),
+                };
+            }
+            Err(e) => return (self.extra.header, Err(e), None),
+        };
+        let continue_on_error = self.callbacks.continue_on_error;
+        let ((), header, result, archive) =
+            self.process_file_keeping::<Extract>(path.as_deref(), file.as_deref(), ());
+        match result {
+            Err(e) if !continue_on_error || e.code != Code::BadData => (header, Err(e), None),
+            result => (header, result.map(|()| outcome), Some(archive)),
+        }
+    }
+
+    /// extracts the entry into `base` like [`extract_with_base`](Self::extract_with_base),
+    /// but creates a [file reference](FileHeader::is_reference) as a copy of the file its
+    /// source was extracted to according to `extracted`, which maps the entries processed
+    /// so far to their path on disk and is updated accordingly.
+    pub(crate) fn extract_resolving_references(
+        self,
+        base: &Path,
+        extracted: &mut HashMap<PathBuf, PathBuf>,
+    ) -> UnrarResult<OpenArchive<Process, CursorBeforeHeader>> {
+        let Some(filename) = self.stripped_filename()? else {
+            return self.skip();
+        };
+        let entry = self.entry();
+        let dest = base.join(filename);
+        let (dest, skip) = match self.overwrite_target(&dest) {
+            // a skipped file already exists, so references to it can still be copied
+            Target::Skip => (dest, true),
+            Target::Rename(renamed) => (renamed, false),
+            Target::Keep => (dest, false),
+        };
+        let hardlink =
+            self.callbacks.recreate_links && entry.redirect_type() == Some(RedirectType::Hardlink);
+        let source = (entry.is_reference() || hardlink)
+            .then(|| {
+                entry
+                    .redirect_target()
+                    .and_then(|target| extracted.get(target))
+            })
+            .flatten();
+        match source {
+            Some(source) if !skip => {
+                let created = match hardlink {
+                    true => create_hardlink(source, &dest),
+                    false => copy_reference(entry, source, &dest),
+                };
+                created.map_err(|e| {
+                    UnrarError::from(Code::ECreate, When::Process)
+                        .with_file(&entry.filename)
+                        .with_source(e)
+                })?;
+                extracted.insert(entry.filename.clone(), dest);
+                self.skip()
+            }
+            _ => {
+                extracted.insert(entry.filename.clone(), dest);
+                match skip {
+                    true => self.skip(),
+                    false => self.extract_with_base(base),
+                }
+            }
+        }
+    }
+}
+
+/// creates `dest` as a hard link to `source`, replacing an existing file.
+fn create_hardlink(source: &Path, dest: &Path) -> std::io::Result<()> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if dest.symlink_metadata().is_ok() {
+        std::fs::remove_file(dest)?;
+    }
+    std::fs::hard_link(source, dest)
+}
+
+/// creates the file reference `entry` at `dest` by copying `source`.
+fn copy_reference(entry: &FileHeader, source: &Path, dest: &Path) -> std::io::Result<()> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::copy(source, dest)?;
+    if let Some(mtime) = entry.modified_time() {
+        std::fs::File::options()
+            .write(true)
+            .open(dest)?
+            .set_modified(mtime)?;
+    }
+    Ok(())
+}
+
+/// sets the modification time of the extracted directories in `dirs`, innermost first.
+/// Like the UnRAR library does for files, failing to set it is not an error.
+pub(crate) fn restore_dir_times(dirs: &[(PathBuf, SystemTime)]) {
+    for (dir, mtime) in dirs.iter().rev() {
+        let mut options = std::fs::File::options();
+        options.read(true);
+        #[cfg(windows)]
+        {
+            use std::os::windows::fs::OpenOptionsExt;
+            // FILE_WRITE_ATTRIBUTES, and FILE_FLAG_BACKUP_SEMANTICS to open a directory
+            options.access_mode(0x100).custom_flags(0x0200_0000);
+        }
+        let _ = options.open(dir).and_then(|dir| dir.set_modified(*mtime));
+    }
+}
+
+/// a nul in a destination path, only found out about once processing.
+fn process_nul_error(e: NulError) -> UnrarError {
+    let mut err: UnrarError = e.into();
+    err.when = When::Process;
+    err
+}
+
+/// opens the archive at `filename`, reading the archive comment into `comment` if given.
+/// The data is returned along with the handle, which is `None` if opening failed.
+fn open_handle(
+    filename: &Path,
+    mode: c_uint,
+    callbacks: &mut Callbacks,
+    comment: Option<&mut [native::WCHAR]>,
+) -> UnrarResult<(Option<Handle>, native::OpenArchiveDataEx)> {
+    let name = pathed::construct(filename)?;
+    let mut data = native::OpenArchiveDataEx::new(name.as_ptr() as *const _, mode);
+    if let Some(comment) = comment {
+        data.comment_buffer_w = comment.as_mut_ptr();
+        data.comment_buffer_size = comment.len() as c_uint;
+    }
+    if callbacks.allow_partial {
+        data.op_flags |= native::ROADOF_KEEPBROKEN;
+    }
+    // archives with encrypted headers may already ask for the password while opening.
+    // Without a callback, the library skips over the encrypted headers instead.
+    let has_password_callback = callbacks.password.is_some();
+    let mut userdata = Userdata::new((), callbacks, None);
+    if has_password_callback {
+        data.callback = Some(Internal::<Skip>::callback);
+        data.user_data = &mut userdata as *mut _ as native::LPARAM;
+    }
+    ffi_event!(TRACE, archive = %filename.display(), mode, "RAROpenArchiveEx");
+    let handle = NonNull::new(unsafe { native::RAROpenArchiveEx(&mut data as *mut _) } as *mut _)
+        .map(Handle);
+    ffi_event!(
+        DEBUG,
+        archive = %filename.display(),
+        result = ?Code::from(data.open_result as i32),
+        "RAROpenArchiveEx returned",
+    );
+    if let Some(panic) = userdata.panic.take() {
+        drop(handle);
+        std::panic::resume_unwind(panic);
+    }
+    Ok((handle, data))
+}
+
+/// converts a wide string decoded by the UnRAR library into UTF-8, restoring the original
+/// bytes the library could not decode. On Unix, it maps such bytes into the private use area
+/// starting at 0xE000 and marks the string with 0xFFFE, see `CharToWideMap` in unicode.cpp.
+fn wide_to_bytes(wide: &[native::WCHAR]) -> Vec<u8> {
+    let wide: Vec<widestring::WideChar> = wide.iter().map(|&c| c as _).collect();
+    let mut bytes = Vec::with_capacity(wide.len());
+    for c in widestring::WideStr::from_slice(&wide).chars_lossy() {
+        match c as u32 {
+            0xFFFE => {}
+            mapped @ 0xE080..=0xE0FF => bytes.push((mapped - 0xE000) as u8),
+            _ => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+    bytes
+}
+
+fn read_header(handle: &Handle, callbacks: &mut Callbacks) -> UnrarResult<Option<FileHeader>> {
+    let mut buffers = callbacks.header_buffers.take().unwrap_or_default();
+    let result = read_header_into(handle, callbacks, &mut buffers);
+    callbacks.header_buffers = Some(buffers);
+    result
 }
 
-fn read_header(handle: &Handle) -> UnrarResult<Option<FileHeader>> {
-    let mut userdata: Userdata<<Skip as ProcessMode>::Output> = Default::default();
+/// reads the next header, with the link target and comment written into `buffers`.
+fn read_header_into(
+    handle: &Handle,
+    callbacks: &mut Callbacks,
+    buffers: &mut HeaderBuffers,
+) -> UnrarResult<Option<FileHeader>> {
+    let HeaderBuffers {
+        redir_name,
+        comment,
+    } = buffers;
+    let mut userdata = Userdata::new((), callbacks, None);
     unsafe {
         native::RARSetCallback(
             handle.0.as_ptr(),
@@ -443,15 +2460,49 @@ fn read_header(handle: &Handle) -> UnrarResult<Option<FileHeader>> {
             &mut userdata as *mut _ as native::LPARAM,
         );
     }
-    let mut header = native::HeaderDataEx::default();
+    let mut header = native::HeaderDataEx {
+        redir_name: redir_name.as_mut_ptr(),
+        redir_name_size: REDIR_NAME_BUFFER_SIZE as c_uint,
+        comment_buffer: comment.as_mut_ptr() as *mut _,
+        comment_buffer_size: FILE_COMMENT_BUFFER_SIZE as c_uint,
+        ..Default::default()
+    };
+    ffi_event!(TRACE, "RARReadHeaderEx");
     let read_result =
-        Code::from(unsafe { native::RARReadHeaderEx(handle.0.as_ptr(), &mut header as *mut _) })
-            .unwrap();
-    match read_result {
-        Code::Success => Ok(Some(header.into())),
+        Code::from(unsafe { native::RARReadHeaderEx(handle.0.as_ptr(), &mut header as *mut _) });
+    let mut entry_comment = None;
+    userdata.resume_panic();
+    let result = match read_result {
+        Code::Success => {
+            // 1 if read or `ERAR_SMALL_BUF` if truncated, `comment_size` includes the nul
+            if header.comment_state == 1 || header.comment_state == native::ERAR_SMALL_BUF as u32 {
+                let len = (header.comment_size as usize).saturating_sub(1);
+                entry_comment = Some(comment[..len.min(comment.len())].to_vec());
+            }
+            let mut entry = FileHeader::from(header);
+            entry.comment = entry_comment;
+            if entry.redir_type != native::FSREDIR_NONE {
+                // RAR4 stores the target of Unix symlinks as their data instead
+                let target = unsafe {
+                    widestring::WideCString::from_ptr_truncate(
+                        redir_name.as_ptr() as *const _,
+                        redir_name.len(),
+                    )
+                };
+                entry.redirect_target = (!target.is_empty()).then(|| wide_to_path(&target));
+            }
+            Ok(Some(entry))
+        }
         Code::EndArchive => Ok(None),
         _ => Err(UnrarError::from(read_result, When::Read)),
-    }
+    };
+    ffi_event!(
+        DEBUG,
+        result = ?read_result,
+        entry = ?result.as_ref().ok().and_then(Option::as_ref).map(|entry| &entry.filename),
+        "RARReadHeaderEx returned",
+    );
+    result
 }
 
 #[derive(Debug)]
@@ -463,37 +2514,86 @@ struct Extract;
 #[derive(Debug)]
 struct Test;
 
-trait ProcessMode: core::fmt::Debug {
+#[derive(Debug)]
+struct ToWriter<W>(std::marker::PhantomData<W>);
+
+struct Sink<W> {
+    writer: W,
+    error: Option<std::io::Error>,
+}
+
+trait ProcessMode {
     const OPERATION: private::Operation;
-    type Output: core::fmt::Debug + std::default::Default;
+    type Output;
 
-    fn process_data(data: &mut Self::Output, other: &[u8]);
+    /// handles a chunk of unpacked data. Returning `false` aborts processing.
+    fn process_data(data: &mut Self::Output, other: &[u8]) -> bool;
 }
 impl ProcessMode for Skip {
     const OPERATION: private::Operation = private::Operation::Skip;
     type Output = ();
 
-    fn process_data(_: &mut Self::Output, _: &[u8]) {}
+    fn process_data(_: &mut Self::Output, _: &[u8]) -> bool {
+        true
+    }
 }
 impl ProcessMode for ReadToVec {
     const OPERATION: private::Operation = private::Operation::Test;
     type Output = Vec<u8>;
 
-    fn process_data(my: &mut Self::Output, other: &[u8]) {
+    fn process_data(my: &mut Self::Output, other: &[u8]) -> bool {
         my.extend_from_slice(other);
+        true
     }
 }
 impl ProcessMode for Extract {
     const OPERATION: private::Operation = private::Operation::Extract;
     type Output = ();
 
-    fn process_data(_: &mut Self::Output, _: &[u8]) {}
+    fn process_data(_: &mut Self::Output, _: &[u8]) -> bool {
+        true
+    }
 }
 impl ProcessMode for Test {
     const OPERATION: private::Operation = private::Operation::Test;
     type Output = ();
 
-    fn process_data(_: &mut Self::Output, _: &[u8]) {}
+    fn process_data(_: &mut Self::Output, _: &[u8]) -> bool {
+        true
+    }
+}
+impl<W: Write> ProcessMode for ToWriter<W> {
+    const OPERATION: private::Operation = private::Operation::Test;
+    type Output = Sink<W>;
+
+    fn process_data(sink: &mut Self::Output, other: &[u8]) -> bool {
+        match sink.writer.write_all(other) {
+            Ok(()) => true,
+            Err(e) => {
+                sink.error = Some(e);
+                false
+            }
+        }
+    }
+}
+
+/// wraps another process mode, computing the hash of the data it processes.
+#[derive(Debug)]
+struct Verify<M>(std::marker::PhantomData<M>);
+
+struct Verified<T> {
+    data: T,
+    hasher: Hasher,
+}
+
+impl<M: ProcessMode> ProcessMode for Verify<M> {
+    const OPERATION: private::Operation = M::OPERATION;
+    type Output = Verified<M::Output>;
+
+    fn process_data(verified: &mut Self::Output, other: &[u8]) -> bool {
+        verified.hasher.update(other);
+        M::process_data(&mut verified.data, other)
+    }
 }
 
 struct Internal<M: ProcessMode> {
@@ -511,35 +2611,110 @@ impl<M: ProcessMode> Internal<M> {
             return 0;
         }
         let user_data = unsafe { &mut *(user_data as *mut Userdata<M::Output>) };
+        // panics must not unwind into the UnRAR library, so abort the operation
+        // and resume the panic once it returned.
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            Self::handle_message(msg, user_data, p1, p2)
+        }));
+        result.unwrap_or_else(|panic| {
+            user_data.panic = Some(panic);
+            -1
+        })
+    }
+
+    fn handle_message(
+        msg: native::UINT,
+        user_data: &mut Userdata<M::Output>,
+        p1: native::LPARAM,
+        p2: native::LPARAM,
+    ) -> c_int {
         match msg {
             native::UCM_CHANGEVOLUMEW => {
-                // 2048 seems to be the buffer size in unrar,
-                // also it's the maximum path length since 5.00.
-                let next =
-                    unsafe { widestring::WideCString::from_ptr_truncate(p1 as *const _, 2048) };
-                user_data.1 = Some(next);
+                // the name is nul-terminated, but only asking for a volume passes a buffer
+                // of volume_buffer_size(), notifying about it passes just the name.
+                let next = unsafe { widestring::WideCStr::from_ptr_str(p1 as *const _) };
+                user_data.volume = Some(next.to_ucstring());
                 match p2 {
-                    // Next volume not found. -1 means stop
-                    native::RAR_VOL_ASK => -1,
+                    // Next volume not found, ask the callback where it is.
+                    // A positive value means try again with the (possibly updated) path, -1 means stop
+                    native::RAR_VOL_ASK => match user_data
+                        .callbacks
+                        .request_volume(p1 as *mut _, volume_buffer_size())
+                    {
+                        true => 1,
+                        false => -1,
+                    },
                     // Next volume found, 0 means continue
                     _ => 0,
                 }
             }
             native::UCM_PROCESSDATA => {
+                // -1 makes the library stop processing
+                if user_data.callbacks.is_cancelled() {
+                    user_data.aborted = Some(Code::Cancelled);
+                    return -1;
+                }
+                // the header may lie about the unpacked size, so count what is actually unpacked
+                user_data.unpacked += p2 as u64;
+                if !user_data
+                    .callbacks
+                    .count_unpacked(user_data.unpacked, p2 as u64)
+                {
+                    user_data.aborted = Some(Code::SizeLimitExceeded);
+                    return -1;
+                }
                 let raw_slice = std::ptr::slice_from_raw_parts(p1 as *const u8, p2 as _);
-                M::process_data(&mut user_data.0, unsafe { &*raw_slice as &_ });
-                0
+                if let (Some(progress), Some(entry)) =
+                    (user_data.callbacks.progress.as_mut(), user_data.entry)
+                {
+                    progress(entry, p2 as usize);
+                }
+                match M::process_data(&mut user_data.data, unsafe { &*raw_slice as &_ }) {
+                    true => 0,
+                    false => -1,
+                }
+            }
+            native::UCM_NEEDPASSWORDW => {
+                match user_data
+                    .callbacks
+                    .request_password(p1 as *mut _, p2 as usize)
+                {
+                    // positive value means continue
+                    true => 1,
+                    // -1 means cancel, ending in `Code::MissingPassword`
+                    false => -1,
+                }
             }
+            // only asked for if the wide variant failed, i.e. no password was provided
+            native::UCM_NEEDPASSWORD => -1,
             _ => 0,
         }
     }
 
     fn process_file_raw(
         handle: &Handle,
+        callbacks: &mut Callbacks,
+        entry: Option<&FileHeader>,
+        path: Option<&pathed::RarStr>,
+        file: Option<&pathed::RarStr>,
+    ) -> UnrarResult<M::Output>
+    where
+        M::Output: Default,
+    {
+        let (data, result) =
+            Self::process_file_with(handle, callbacks, entry, path, file, Default::default());
+        result.map(|_| data)
+    }
+
+    fn process_file_with(
+        handle: &Handle,
+        callbacks: &mut Callbacks,
+        entry: Option<&FileHeader>,
         path: Option<&pathed::RarStr>,
         file: Option<&pathed::RarStr>,
-    ) -> UnrarResult<M::Output> {
-        let mut user_data: Userdata<M::Output> = Default::default();
+        data: M::Output,
+    ) -> (M::Output, UnrarResult<()>) {
+        let mut user_data = Userdata::new(data, callbacks, entry);
         unsafe {
             native::RARSetCallback(
                 handle.0.as_ptr(),
@@ -547,29 +2722,61 @@ impl<M: ProcessMode> Internal<M> {
                 &mut user_data as *mut _ as native::LPARAM,
             );
         }
+        ffi_event!(
+            TRACE,
+            entry = ?entry.map(|entry| &entry.filename),
+            operation = ?M::OPERATION,
+            "RARProcessFile",
+        );
         let process_result = Code::from(pathed::process_file(
             handle.0.as_ptr(),
             M::OPERATION as i32,
             path,
             file,
-        ))
-        .unwrap();
-        match process_result {
-            Code::Success => Ok(user_data.0),
-            _ => Err(UnrarError::from(process_result, When::Process)),
-        }
+        ));
+        ffi_event!(
+            DEBUG,
+            entry = ?entry.map(|entry| &entry.filename),
+            operation = ?M::OPERATION,
+            result = ?process_result,
+            "RARProcessFile returned",
+        );
+        user_data.resume_panic();
+        let result = match (user_data.aborted, process_result) {
+            // the library only knows the operation was aborted
+            (Some(reason), _) => Err(UnrarError::from(reason, When::Process)),
+            (None, Code::Success) => Ok(()),
+            (None, code) => Err(UnrarError::from(code, When::Process)),
+        };
+        let result = match entry {
+            Some(entry) => result.map_err(|e| e.with_file(&entry.filename)),
+            None => result,
+        };
+        (user_data.data, result)
     }
 }
 
 bitflags::bitflags! {
-    #[derive(Debug)]
-    struct EntryFlags: u32 {
-        const SPLIT_BEFORE = 0x1;
-        const SPLIT_AFTER = 0x2;
-        const ENCRYPTED = 0x4;
-        // const RESERVED = 0x8;
-        const SOLID = 0x10;
-        const DIRECTORY = 0x20;
+    /// The flags of an entry, see [`FileHeader::flags`].
+    ///
+    /// These are the `RHDF_*` flags the UnRAR library reports for RAR4 and RAR5 archives
+    /// alike, so they do not depend on how each format stores them.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct EntryFlags: u32 {
+        /// the entry is continued from the previous volume, see [`FileHeader::is_split_before`]
+        const SPLIT_BEFORE = native::RHDF_SPLITBEFORE;
+        /// the entry continues in the next volume, see [`FileHeader::is_split_after`]
+        const SPLIT_AFTER = native::RHDF_SPLITAFTER;
+        /// the entry's data is encrypted, see [`FileHeader::is_encrypted`]
+        const ENCRYPTED = native::RHDF_ENCRYPTED;
+        /// the entry has a comment, see [`FileHeader::comment_bytes`]. The library leaves
+        /// this bit reserved, it is set from the comment read along with the header.
+        const COMMENT = 0x8;
+        /// the entry is unpacked using the data of the entries before it in a solid archive
+        const SOLID = native::RHDF_SOLID;
+        /// the entry is a directory, see [`FileHeader::is_directory`]
+        const DIRECTORY = native::RHDF_DIRECTORY;
     }
 }
 
@@ -577,19 +2784,511 @@ bitflags::bitflags! {
 ///
 /// Created using the read_header methods in an OpenArchive, contains
 /// information for the file that follows which is to be processed next.
+///
+/// It owns all of its data, so it can be kept around and compared after the archive moved on.
+///
+/// # Serialization
+///
+/// With the `serde` feature, a header serializes as a struct of all of its fields under
+/// the names they have here, the private ones included, so that a deserialized header
+/// equals the one read:
+///   - `filename`, `redirect_target` and `volume`, the archive file the header was read
+///     from, as strings. Serializing fails for paths that are not valid UTF-8.
+///   - `flags` as the names of its [flags](EntryFlags) joined by ` | ` in human-readable
+///     formats such as JSON, and as the bits in binary formats.
+///   - `host_os` and `hash` as externally tagged enums, e.g. `"Unix"`, `{"Unknown": 9}` or
+///     `{"Crc32": 1234}`.
+///   - `comment` and `raw` as arrays of bytes.
+///   - the times as stored: `file_time` as the MS-DOS date and time, and `mtime`, `ctime`
+///     and `atime` as the 100 ns intervals since 1601-01-01 UTC, 0 if not stored.
+///   - all other fields as the numbers the UnRAR library reports.
+///
+/// Field names and their representation only change in major versions.
 #[allow(missing_docs)]
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FileHeader {
     pub filename: PathBuf,
     flags: EntryFlags,
     pub unpacked_size: u64,
+    packed_size: u64,
     pub file_crc: u32,
     pub file_time: u32,
     pub method: u32,
     pub file_attr: u32,
+    host_os: HostOs,
+    unp_ver: u32,
+    dict_size: u32,
+    redir_type: u32,
+    redirect_target: Option<PathBuf>,
+    comment: Option<Vec<u8>>,
+    raw: Vec<u8>,
+    mtime: u64,
+    ctime: u64,
+    atime: u64,
+    hash: StoredHash,
+    /// the volume the header was read from
+    pub(crate) volume: PathBuf,
+}
+
+impl From<&native::HeaderDataEx> for StoredHash {
+    fn from(header: &native::HeaderDataEx) -> Self {
+        match header.hash_type {
+            native::RAR_HASH_CRC32 => StoredHash::Crc32(header.file_crc),
+            native::RAR_HASH_BLAKE2 => StoredHash::Blake2sp(header.hash.map(|b| b as u8)),
+            _ => StoredHash::None,
+        }
+    }
+}
+
+/// The operating system an entry was archived on, see [`FileHeader::host_os`].
+///
+/// With the `serde` feature, it serializes as the name of the variant, e.g. `"Win32"`, and
+/// `{"Unknown": 9}` for unknown values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HostOs {
+    /// MS-DOS
+    MsDos,
+    /// OS/2
+    Os2,
+    /// Windows
+    Win32,
+    /// Unix, including Linux and macOS
+    Unix,
+    /// Classic Mac OS
+    MacOs,
+    /// BeOS
+    BeOs,
+    /// Any other value
+    Unknown(u8),
+}
+
+impl From<u32> for HostOs {
+    fn from(host_os: u32) -> Self {
+        match host_os {
+            0 => HostOs::MsDos,
+            1 => HostOs::Os2,
+            2 => HostOs::Win32,
+            3 => HostOs::Unix,
+            4 => HostOs::MacOs,
+            5 => HostOs::BeOs,
+            n => HostOs::Unknown(u8::try_from(n).unwrap_or(u8::MAX)),
+        }
+    }
+}
+
+/// The kind of link an entry is, see [`FileHeader::redirect_type`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedirectType {
+    /// a Unix symbolic link
+    UnixSymlink,
+    /// a Windows symbolic link
+    WindowsSymlink,
+    /// a Windows junction (directory link)
+    Junction,
+    /// a hard link to an earlier entry
+    Hardlink,
+    /// a reference to an earlier entry with the same data, extracted as a copy of it
+    FileReference,
+    /// Any other value
+    Unknown(u8),
+}
+
+/// How an entry's data is compressed, see [`FileHeader::compression_method`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionMethod {
+    /// stored without compression
+    Store,
+    /// fastest compression
+    Fastest,
+    /// fast compression
+    Fast,
+    /// normal compression
+    Normal,
+    /// good compression
+    Good,
+    /// best compression
+    Best,
+    /// Any other value
+    Other(u8),
+}
+
+impl From<u32> for CompressionMethod {
+    fn from(method: u32) -> Self {
+        match method {
+            0x30 => CompressionMethod::Store,
+            0x31 => CompressionMethod::Fastest,
+            0x32 => CompressionMethod::Fast,
+            0x33 => CompressionMethod::Normal,
+            0x34 => CompressionMethod::Good,
+            0x35 => CompressionMethod::Best,
+            n => CompressionMethod::Other(u8::try_from(n).unwrap_or(u8::MAX)),
+        }
+    }
 }
 
 impl FileHeader {
+    /// the compression method of the entry, decoded from the raw [`method`](Self::method).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::{Archive, CompressionMethod};
+    ///
+    /// let mut archive = Archive::new("data/times.rar").open_for_listing().unwrap();
+    /// let entry = archive.next().unwrap().unwrap();
+    /// assert_eq!(entry.compression_method(), CompressionMethod::Store);
+    /// ```
+    pub fn compression_method(&self) -> CompressionMethod {
+        self.method.into()
+    }
+
+    /// is this entry's data stored without compression, i.e. its
+    /// [compression method](Self::compression_method) is [`CompressionMethod::Store`].
+    ///
+    /// The data of a stored entry is still unpacked by the UnRAR library, which checks its
+    /// hash and decrypts it, but that is much faster than decompressing it.
+    pub fn is_stored(&self) -> bool {
+        self.compression_method() == CompressionMethod::Store
+    }
+
+    /// the version of the RAR format needed to unpack the entry, times ten,
+    /// e.g. 29 for RAR 2.9 or 50 for RAR 5.0.
+    pub fn min_version(&self) -> u8 {
+        u8::try_from(self.unp_ver).unwrap_or(u8::MAX)
+    }
+
+    /// the size in bytes of the dictionary the entry was compressed with, which is about
+    /// the memory needed to unpack it, or `None` for directories and entries of RAR4 archives.
+    ///
+    /// RAR5 archives store it in the compression information of the entry's header,
+    /// up to 1 GiB for RAR 5.0 and more for RAR 7.0. RAR4 archives only have a flag choosing
+    /// one from 64 KiB up to 4 MiB. Check it before unpacking in constrained environments,
+    /// or [`OpenArchive::dictionary_size`] for the whole archive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::Archive;
+    ///
+    /// let mut archive = Archive::new("data/solid.rar").open_for_listing().unwrap();
+    /// let entry = archive.next().unwrap().unwrap();
+    /// assert_eq!(entry.dictionary_size(), Some(1 << 20));
+    /// let mut archive = Archive::new("data/version.rar").open_for_listing().unwrap();
+    /// let entry = archive.next().unwrap().unwrap();
+    /// assert_eq!(entry.dictionary_size(), None);
+    /// ```
+    pub fn dictionary_size(&self) -> Option<u64> {
+        match self.dict_size {
+            // RAR5 entries need version 5.0 or later
+            _ if self.unp_ver < 50 => None,
+            0 => None,
+            kib => Some(kib as u64 * 1024),
+        }
+    }
+
+    /// the kind of link the entry is, or `None` if it is a regular file or directory.
+    ///
+    /// Extracting a symbolic link or junction to disk already creates it as such,
+    /// given the permission to; reading it yields its data, which is empty in RAR5 archives.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::{Archive, RedirectType};
+    ///
+    /// let entries = Archive::new("data/symlink.rar").list().unwrap();
+    /// assert_eq!(entries[0].redirect_type(), None);
+    /// assert_eq!(entries[1].redirect_type(), Some(RedirectType::UnixSymlink));
+    /// ```
+    pub fn redirect_type(&self) -> Option<RedirectType> {
+        match self.redir_type {
+            native::FSREDIR_NONE => None,
+            native::FSREDIR_UNIXSYMLINK => Some(RedirectType::UnixSymlink),
+            native::FSREDIR_WINSYMLINK => Some(RedirectType::WindowsSymlink),
+            native::FSREDIR_JUNCTION => Some(RedirectType::Junction),
+            native::FSREDIR_HARDLINK => Some(RedirectType::Hardlink),
+            native::FSREDIR_FILECOPY => Some(RedirectType::FileReference),
+            n => Some(RedirectType::Unknown(u8::try_from(n).unwrap_or(u8::MAX))),
+        }
+    }
+
+    /// is the entry a reference to an earlier entry with the same data (`rar -oi`),
+    /// i.e. its [redirect type](Self::redirect_type) is [`RedirectType::FileReference`].
+    ///
+    /// A reference has no data of its own, it is extracted by copying the file its source
+    /// was extracted to. The UnRAR library fails to find that file with
+    /// [`Code::EReference`] unless it is in the working directory, which
+    /// [`Archive::extract_all_resolving_references`](crate::Archive::extract_all_resolving_references)
+    /// works around.
+    pub fn is_reference(&self) -> bool {
+        self.redir_type == native::FSREDIR_FILECOPY
+    }
+
+    /// the path the link points to, for entries with a [redirect type](Self::redirect_type).
+    ///
+    /// Hard links and file references point to another entry of the archive, symbolic links
+    /// anywhere. RAR4 archives store the target of Unix symbolic links as their data instead,
+    /// so this is `None` for them.
+    pub fn redirect_target(&self) -> Option<&Path> {
+        self.redirect_target.as_deref()
+    }
+
+    /// the [`filename`](Self::filename) for display, with anything that is not valid
+    /// Unicode replaced by `U+FFFD`.
+    ///
+    /// The filename itself keeps the name as stored: RAR5 and Unicode RAR4 names are
+    /// converted from UTF-8 and UTF-16, while on Unix the bytes of RAR4 names in a legacy
+    /// codepage are kept as they are, as the library does when extracting.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// let mut archive = Archive::new("data/legacy.rar").open_for_listing().unwrap();
+    /// let entry = archive.next().unwrap().unwrap();
+    /// // stored in Latin-1 as `caf\xe9.txt`
+    /// # #[cfg(unix)]
+    /// assert_eq!(entry.filename_lossy(), "caf\u{FFFD}.txt");
+    /// ```
+    pub fn filename_lossy(&self) -> String {
+        self.filename.to_string_lossy().into_owned()
+    }
+
+    /// the entry's comment, separate from the [archive comment](OpenArchive::comment_bytes),
+    /// with invalid UTF-8 replaced, see [`comment_bytes`](Self::comment_bytes).
+    pub fn comment(&self) -> Option<String> {
+        self.comment_bytes()
+            .map(|comment| String::from_utf8_lossy(comment).into_owned())
+    }
+
+    /// the entry's comment as stored in the archive, or `None` if it has none.
+    ///
+    /// Only RAR 2.x archives store comments for entries. The UnRAR library does not read
+    /// them as of version 7, so this is `None` unless a future version of it does.
+    /// Comments longer than 4 KiB are truncated.
+    pub fn comment_bytes(&self) -> Option<&[u8]> {
+        self.comment.as_deref()
+    }
+
+    /// the header data the UnRAR library filled in for the entry, for fields not exposed
+    /// otherwise: the bytes of its `RARHeaderDataEx` structure from `Flags` up to `Reserved`,
+    /// in native byte order and without the filenames. Pointers to buffers are zeroed.
+    ///
+    /// The layout depends on the version of the UnRAR library and the platform, see
+    /// `dll.hpp` in the UnRAR sources. It is not covered by semver, so prefer the accessors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::Archive;
+    ///
+    /// let mut archive = Archive::new("data/version.rar").open_for_listing().unwrap();
+    /// let entry = archive.next().unwrap().unwrap();
+    /// // `Flags` comes first, followed by `PackSize`
+    /// let pack_size = u32::from_ne_bytes(entry.raw_header()[4..8].try_into().unwrap());
+    /// assert_eq!(pack_size as u64, entry.packed_size());
+    /// ```
+    pub fn raw_header(&self) -> &[u8] {
+        &self.raw
+    }
+
+    /// the operating system the entry was archived on, which tells how to interpret
+    /// [`file_attr`](Self::file_attr): Unix permission bits for [`HostOs::Unix`],
+    /// Windows file attributes otherwise.
+    ///
+    /// The UnRAR library reports all entries as either [`HostOs::Win32`] (including
+    /// MS-DOS and OS/2 in RAR4 archives) or [`HostOs::Unix`].
+    pub fn host_os(&self) -> HostOs {
+        self.host_os
+    }
+
+    /// the Unix permission bits of the entry (including setuid, setgid and sticky bits),
+    /// or `None` if it was not archived on [Unix](HostOs::Unix).
+    ///
+    /// Extracting the entry to disk already applies them on Unix.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::Archive;
+    ///
+    /// let mut archive = Archive::new("data/version.rar").open_for_listing().unwrap();
+    /// assert_eq!(archive.next().unwrap().unwrap().unix_mode(), Some(0o644));
+    /// ```
+    pub fn unix_mode(&self) -> Option<u32> {
+        (self.host_os == HostOs::Unix).then_some(self.file_attr & 0o7777)
+    }
+
+    /// the size of the entry's compressed data in the archive.
+    ///
+    /// For an entry [split](Self::is_split) across volumes, this is the size of the segment
+    /// in the volume the header was read from, not of the entire entry. Listing in
+    /// [`List`] mode reports the first segment, [`ListSplit`] mode each of them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::Archive;
+    ///
+    /// // stored without compression
+    /// let mut archive = Archive::new("data/times.rar").open_for_listing().unwrap();
+    /// let entry = archive.next().unwrap().unwrap();
+    /// assert_eq!(entry.packed_size(), entry.unpacked_size);
+    /// ```
+    pub fn packed_size(&self) -> u64 {
+        self.packed_size
+    }
+
+    /// the [packed size](Self::packed_size) relative to the unpacked size, e.g. 0.25 if the
+    /// entry was compressed to a quarter of its size, or 0 for empty entries.
+    ///
+    /// Values above 1 occur for incompressible data, which grows slightly, or for
+    /// [split](Self::is_split) entries, whose packed size only covers a segment.
+    pub fn compression_ratio(&self) -> f64 {
+        match self.unpacked_size {
+            0 => 0.0,
+            unpacked_size => self.packed_size as f64 / unpacked_size as f64,
+        }
+    }
+
+    /// the CRC32 of the entry's unpacked data, or 0 if the entry does not store one,
+    /// e.g. for directories or RAR5 entries hashed with BLAKE2sp instead.
+    ///
+    /// Unlike [`file_crc`](Self::file_crc), which the UnRAR library may leave at the
+    /// value of a previous entry, this is only set if the entry actually stores a CRC32.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::Archive;
+    ///
+    /// let mut archive = Archive::new("data/version.rar").open_for_listing().unwrap();
+    /// assert_eq!(archive.next().unwrap().unwrap().crc32(), 0xC67D_F345);
+    /// ```
+    pub fn crc32(&self) -> u32 {
+        match self.hash {
+            StoredHash::Crc32(crc) => crc,
+            _ => 0,
+        }
+    }
+
+    /// the BLAKE2sp-256 hash of the entry's unpacked data, or `None` if it does not store one.
+    ///
+    /// Only RAR5 archives created with `-htb` store it, in place of the [CRC32](Self::crc32).
+    /// With the `blake2` feature, [`extract_and_verify`](OpenArchive::extract_and_verify)
+    /// can check the unpacked data against it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::Archive;
+    ///
+    /// let mut archive = Archive::new("data/blake2.rar").open_for_listing().unwrap();
+    /// let hash = archive.next().unwrap().unwrap().blake2sp().unwrap();
+    /// assert_eq!(hash[..4], [0x69, 0x44, 0x44, 0x2e]);
+    ///
+    /// let mut archive = Archive::new("data/version.rar").open_for_listing().unwrap();
+    /// assert_eq!(archive.next().unwrap().unwrap().blake2sp(), None);
+    /// ```
+    pub fn blake2sp(&self) -> Option<[u8; 32]> {
+        match self.hash {
+            StoredHash::Blake2sp(hash) => Some(hash),
+            _ => None,
+        }
+    }
+
+    /// the kind of hash the entry stores to verify its unpacked data, see
+    /// [`stored_hash`](Self::stored_hash) for the digest.
+    ///
+    /// Directories and symbolic links store no hash and are [`HashKind::None`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::{Archive, HashKind};
+    ///
+    /// let mut archive = Archive::new("data/blake2.rar").open_for_listing().unwrap();
+    /// assert_eq!(archive.next().unwrap().unwrap().hash_kind(), HashKind::Blake2sp);
+    /// let mut archive = Archive::new("data/version.rar").open_for_listing().unwrap();
+    /// assert_eq!(archive.next().unwrap().unwrap().hash_kind(), HashKind::Crc32);
+    /// ```
+    pub fn hash_kind(&self) -> HashKind {
+        self.hash.kind()
+    }
+
+    /// the hash of the entry's unpacked data, whichever kind it stores: the same as
+    /// [`crc32`](Self::crc32) or [`blake2sp`](Self::blake2sp), in one value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use unrar::{Archive, StoredHash};
+    ///
+    /// let mut archive = Archive::new("data/version.rar").open_for_listing().unwrap();
+    /// let entry = archive.next().unwrap().unwrap();
+    /// assert_eq!(entry.stored_hash(), StoredHash::Crc32(0xC67D_F345));
+    /// ```
+    pub fn stored_hash(&self) -> StoredHash {
+        self.hash
+    }
+
+    /// the last modification time of the entry, or `None` if the archive does not store it.
+    ///
+    /// For RAR4 archives this is decoded from the DOS timestamp in [`file_time`](Self::file_time),
+    /// which only has a resolution of 2 seconds, unless the archive stores extended time fields.
+    /// RAR5 archives store it either as Unix or Windows time, with up to nanosecond precision.
+    /// The sub-second part is kept, though the UnRAR library reports all times as Windows
+    /// `FILETIME`, so nanoseconds are truncated to 100 ns intervals.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// use unrar::Archive;
+    ///
+    /// let mut archive = Archive::new("data/times.rar").open_for_listing().unwrap();
+    /// let entry = archive.next().unwrap().unwrap();
+    /// assert_eq!(entry.modified_time(), Some(UNIX_EPOCH + Duration::from_secs(1700000000)));
+    /// ```
+    pub fn modified_time(&self) -> Option<SystemTime> {
+        filetime_to_system_time(self.mtime)
+    }
+
+    /// the creation time of the entry, or `None` if the archive does not store it.
+    ///
+    /// Only RAR5 archives created with `-tsc` (and RAR4 archives with extended
+    /// time fields) store it.
+    pub fn created_time(&self) -> Option<SystemTime> {
+        filetime_to_system_time(self.ctime)
+    }
+
+    /// the last access time of the entry, or `None` if the archive does not store it.
+    ///
+    /// Only RAR5 archives created with `-tsa` (and RAR4 archives with extended
+    /// time fields) store it.
+    pub fn accessed_time(&self) -> Option<SystemTime> {
+        filetime_to_system_time(self.atime)
+    }
+
+    /// the entry's flags, e.g. to check several of them at once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::{Archive, EntryFlags};
+    /// let mut entries = Archive::new("data/directory.rar").open_for_listing().unwrap();
+    /// let entry = entries.next().unwrap().unwrap();
+    /// assert!(entry.flags().contains(EntryFlags::DIRECTORY));
+    /// assert!(!entry.flags().intersects(EntryFlags::ENCRYPTED | EntryFlags::COMMENT));
+    /// ```
+    pub fn flags(&self) -> EntryFlags {
+        let mut flags = self.flags;
+        flags.set(EntryFlags::COMMENT, self.comment.is_some());
+        flags
+    }
+
     /// is this entry split across multiple volumes.
     ///
     /// Will also work in open mode [`List`]
@@ -598,14 +3297,20 @@ impl FileHeader {
             || self.flags.contains(EntryFlags::SPLIT_AFTER)
     }
 
-    /// is this entry split across multiple volumes, starting here
+    /// is this entry split across multiple volumes, continuing in the next volume
+    ///
+    /// Reads the `RHDF_SPLITAFTER` (0x02) entry flag, which the UnRAR library sets
+    /// for both RAR4 and RAR5 archives.
     ///
     /// Will also work in open mode [`List`]
     pub fn is_split_after(&self) -> bool {
         self.flags.contains(EntryFlags::SPLIT_AFTER)
     }
 
-    /// is this entry split across multiple volumes, starting here
+    /// is this entry split across multiple volumes, continued from the previous volume
+    ///
+    /// Reads the `RHDF_SPLITBEFORE` (0x01) entry flag, which the UnRAR library sets
+    /// for both RAR4 and RAR5 archives.
     ///
     /// Will always return false in open mode [`List`][^1].
     ///
@@ -616,16 +3321,22 @@ impl FileHeader {
     }
 
     /// is this entry a directory
+    ///
+    /// Reads the `RHDF_DIRECTORY` (0x20) entry flag, which the UnRAR library sets
+    /// for directories in both RAR4 and RAR5 archives.
     pub fn is_directory(&self) -> bool {
         self.flags.contains(EntryFlags::DIRECTORY)
     }
 
-    /// is this entry encrypted
+    /// is this entry's data encrypted, so processing it requires the password.
+    ///
+    /// Reads the `RHDF_ENCRYPTED` (0x04) entry flag. Listing entries does not require
+    /// the password, unless the archive [has encrypted headers](OpenArchive::has_encrypted_headers).
     pub fn is_encrypted(&self) -> bool {
         self.flags.contains(EntryFlags::ENCRYPTED)
     }
 
-    /// is this entry a file
+    /// is this entry a file, i.e. not a [directory](Self::is_directory)
     pub fn is_file(&self) -> bool {
         !self.is_directory()
     }
@@ -646,20 +3357,117 @@ impl fmt::Display for FileHeader {
 
 impl From<native::HeaderDataEx> for FileHeader {
     fn from(header: native::HeaderDataEx) -> Self {
+        // copied out of the packed struct, which may be unaligned
+        let filename_w = header.filename_w;
         let filename = unsafe {
-            widestring::WideCString::from_ptr_truncate(header.filename_w.as_ptr() as *const _, 1024)
+            widestring::WideCString::from_ptr_truncate(filename_w.as_ptr() as *const _, 1024)
+        };
+        let archive_name_w = header.archive_name_w;
+        let volume = unsafe {
+            widestring::WideCString::from_ptr_truncate(archive_name_w.as_ptr() as *const _, 1024)
         };
 
         FileHeader {
-            filename: PathBuf::from(filename.to_os_string()),
+            filename: wide_to_path(&filename),
             flags: EntryFlags::from_bits(header.flags).unwrap(),
             unpacked_size: unpack_unp_size(header.unp_size, header.unp_size_high),
+            packed_size: unpack_unp_size(header.pack_size, header.pack_size_high),
             file_crc: header.file_crc,
             file_time: header.file_time,
             method: header.method,
             file_attr: header.file_attr,
+            host_os: header.host_os.into(),
+            unp_ver: header.unp_ver,
+            dict_size: header.dict_size,
+            redir_type: header.redir_type,
+            redirect_target: None,
+            comment: None,
+            raw: raw_header(&header),
+            mtime: unpack_unp_size(header.mtime_low, header.mtime_high),
+            ctime: unpack_unp_size(header.ctime_low, header.ctime_high),
+            atime: unpack_unp_size(header.atime_low, header.atime_high),
+            hash: (&header).into(),
+            volume: PathBuf::from(volume.to_os_string()),
+        }
+    }
+}
+
+/// the fields of `header` from `flags` up to `reserved` as bytes, with the pointers
+/// to the buffers zeroed, see [`FileHeader::raw_header`].
+fn raw_header(header: &native::HeaderDataEx) -> Vec<u8> {
+    use std::mem::{offset_of, size_of};
+    type Header = native::HeaderDataEx;
+
+    let start = offset_of!(Header, flags);
+    let end = offset_of!(Header, reserved);
+    // the struct is packed, so it has no uninitialized padding bytes
+    let bytes = unsafe { std::slice::from_raw_parts(header as *const Header as *const u8, end) };
+    let mut raw = bytes[start..].to_vec();
+    for pointer in [
+        offset_of!(Header, comment_buffer),
+        offset_of!(Header, redir_name),
+    ] {
+        raw[pointer - start..][..size_of::<*mut u8>()].fill(0);
+    }
+    raw
+}
+
+/// converts a name reported by the library to a path.
+///
+/// On Unix, the library maps the bytes of names in a legacy codepage it cannot convert
+/// to the private use area from `U+E080`, marking such names with `U+FFFE`.
+/// They are mapped back to the original bytes here, as the library does when extracting.
+#[cfg(unix)]
+fn wide_to_path(name: &widestring::WideCStr) -> PathBuf {
+    use std::ffi::OsString;
+    use std::os::unix::ffi::OsStringExt;
+    const MAPPED_STRING_MARK: u32 = 0xFFFE;
+    const MAP_AREA_START: u32 = 0xE000;
+
+    let chars: &[u32] = name.as_slice();
+    if !chars.contains(&MAPPED_STRING_MARK) {
+        return PathBuf::from(name.to_os_string());
+    }
+    let mut bytes = Vec::with_capacity(chars.len());
+    for &c in chars {
+        match c {
+            MAPPED_STRING_MARK => {}
+            c if (MAP_AREA_START + 0x80..MAP_AREA_START + 0x100).contains(&c) => {
+                bytes.push((c - MAP_AREA_START) as u8)
+            }
+            c => {
+                let c = char::from_u32(c).unwrap_or(char::REPLACEMENT_CHARACTER);
+                bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+            }
         }
     }
+    PathBuf::from(OsString::from_vec(bytes))
+}
+
+/// converts a name reported by the library to a path, which is lossless for UTF-16.
+#[cfg(not(unix))]
+fn wide_to_path(name: &widestring::WideCStr) -> PathBuf {
+    PathBuf::from(name.to_os_string())
+}
+
+/// Converts a Windows `FILETIME` (100 ns intervals since 1601-01-01) as reported by
+/// the UnRAR library, where 0 means the time is not set.
+fn filetime_to_system_time(filetime: u64) -> Option<SystemTime> {
+    // 100 ns intervals between 1601-01-01 and 1970-01-01
+    const UNIX_EPOCH_FILETIME: u64 = 116_444_736_000_000_000;
+    if filetime == 0 {
+        return None;
+    }
+    let since = |intervals: u64| {
+        Duration::new(
+            intervals / 10_000_000,
+            (intervals % 10_000_000) as u32 * 100,
+        )
+    };
+    match filetime.checked_sub(UNIX_EPOCH_FILETIME) {
+        Some(after) => SystemTime::UNIX_EPOCH.checked_add(since(after)),
+        None => SystemTime::UNIX_EPOCH.checked_sub(since(UNIX_EPOCH_FILETIME - filetime)),
+    }
 }
 
 fn unpack_unp_size(unp_size: c_uint, unp_size_high: c_uint) -> u64 {
@@ -668,10 +3476,177 @@ fn unpack_unp_size(unp_size: c_uint, unp_size_high: c_uint) -> u64 {
 
 #[cfg(test)]
 mod tests {
+    #[test]
+    fn raw_header() {
+        let mut comment = [0u8; 4];
+        let header = super::native::HeaderDataEx {
+            flags: 0x20,
+            pack_size: 7,
+            comment_buffer: comment.as_mut_ptr() as *mut _,
+            ..Default::default()
+        };
+        let entry = super::FileHeader::from(header);
+        let raw = entry.raw_header();
+        assert_eq!(raw[..4], 0x20u32.to_ne_bytes());
+        assert_eq!(raw[4..8], 7u32.to_ne_bytes());
+        // the pointer does not leak into the bytes
+        let pointer = (comment.as_ptr() as usize).to_ne_bytes();
+        assert!(!raw.windows(pointer.len()).any(|w| w == pointer));
+    }
+
+    #[cfg(feature = "static")]
+    #[test]
+    fn volume_buffer_size() {
+        // the bundled library is UnRAR 7
+        assert_eq!(super::volume_buffer_size(), super::VOLUME_BUFFER_SIZE);
+    }
+
+    #[test]
+    fn entry_flags() {
+        use super::EntryFlags;
+        let header = super::native::HeaderDataEx {
+            flags: 0x24,
+            ..Default::default()
+        };
+        let mut entry = super::FileHeader::from(header);
+        assert_eq!(entry.flags(), EntryFlags::DIRECTORY | EntryFlags::ENCRYPTED);
+        entry.comment = Some(b"comment".to_vec());
+        assert!(entry.flags().contains(EntryFlags::COMMENT));
+    }
+
+    #[test]
+    fn signature_format() {
+        use super::{signature_format, RarFormat};
+        assert_eq!(signature_format(b"Rar!\x1a\x07\x00\xcf"), RarFormat::Rar15);
+        assert_eq!(signature_format(b"Rar!\x1a\x07\x01\x00"), RarFormat::Rar50);
+        assert_eq!(signature_format(b"RE~^"), RarFormat::Rar14);
+        // self-extracting, after the executable
+        assert_eq!(
+            signature_format(b"MZ\x90Rar\0Rar!\x1a\x07\x01\x00"),
+            RarFormat::Rar50
+        );
+        assert_eq!(
+            signature_format(b"Rar!\x1a\x07\x02\x00"),
+            RarFormat::Unknown
+        );
+        assert_eq!(signature_format(b"Rar!\x1a"), RarFormat::Unknown);
+    }
+
+    #[test]
+    fn detect_format() {
+        use super::{detect_format, RarFormat};
+        assert_eq!(detect_format(b"Rar!\x1a\x07\x00"), Some(RarFormat::Rar15));
+        assert_eq!(
+            detect_format(b"Rar!\x1a\x07\x01\x00"),
+            Some(RarFormat::Rar50)
+        );
+        assert_eq!(detect_format(b"RE~^"), Some(RarFormat::Rar14));
+        assert_eq!(
+            detect_format(b"Rar!\x1a\x07\x03\x00"),
+            Some(RarFormat::Unknown)
+        );
+        assert_eq!(detect_format(b"Rar!\x1a"), None);
+        assert_eq!(detect_format(b""), None);
+        // self-extracting archives are not looked into
+        assert_eq!(detect_format(b"MZRar!\x1a\x07\x01\x00"), None);
+    }
+
     #[test]
     fn combine_size() {
         use super::unpack_unp_size;
         let (high, low) = (1u32, 1464303715u32);
         assert_eq!(unpack_unp_size(low, high), 5759271011);
     }
+
+    #[test]
+    fn large_entry_sizes() {
+        let header = super::native::HeaderDataEx {
+            unp_size: 0x0000_0010,
+            unp_size_high: 0x0000_0002,
+            pack_size: 0xFFFF_FFFF,
+            pack_size_high: 0x0000_0001,
+            ..Default::default()
+        };
+        let entry = super::FileHeader::from(header);
+        assert_eq!(entry.unpacked_size, 0x2_0000_0010);
+        assert_eq!(entry.packed_size(), 0x1_FFFF_FFFF);
+    }
+
+    #[test]
+    fn convert_filetime() {
+        use super::filetime_to_system_time;
+        use std::time::{Duration, UNIX_EPOCH};
+        assert_eq!(filetime_to_system_time(0), None);
+        assert_eq!(
+            filetime_to_system_time(116_444_736_000_000_000),
+            Some(UNIX_EPOCH)
+        );
+        assert_eq!(
+            filetime_to_system_time(116_444_736_000_000_015),
+            Some(UNIX_EPOCH + Duration::from_nanos(1500))
+        );
+        assert_eq!(
+            filetime_to_system_time(116_444_735_990_000_000),
+            UNIX_EPOCH.checked_sub(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn contained_paths() {
+        use super::is_contained;
+        use std::path::Path;
+        assert!(is_contained(Path::new("dir/file.txt")));
+        assert!(is_contained(Path::new("./dir/../file.txt")));
+        assert!(!is_contained(Path::new("../file.txt")));
+        assert!(!is_contained(Path::new("dir/../../file.txt")));
+        assert!(!is_contained(Path::new("/etc/passwd")));
+        #[cfg(windows)]
+        {
+            assert!(!is_contained(Path::new(r"C:\Windows\file.txt")));
+            assert!(!is_contained(Path::new(r"C:file.txt")));
+            assert!(!is_contained(Path::new(r"\\?\C:\file.txt")));
+            assert!(!is_contained(Path::new(r"dir\..\..\file.txt")));
+            assert!(!is_contained(Path::new(r"dir\NUL")));
+            assert!(!is_contained(Path::new("file.txt:stream")));
+        }
+    }
+
+    #[test]
+    fn special_windows_names() {
+        use super::is_special_windows_name;
+        for name in [
+            "CON",
+            "nul",
+            "Aux.txt",
+            "com1",
+            "LPT9.tar.gz",
+            "nul ",
+            "prn .txt",
+        ] {
+            assert!(is_special_windows_name(name.as_ref()), "{name}");
+        }
+        assert!(is_special_windows_name("file.txt:stream".as_ref()));
+        assert!(is_special_windows_name("file.txt::$DATA".as_ref()));
+        for name in ["console", "nul_", "com", "COM10", "file.txt", "aux-1.txt"] {
+            assert!(!is_special_windows_name(name.as_ref()), "{name}");
+        }
+    }
+
+    #[test]
+    fn grow_comment_buffer() {
+        let archive = crate::Archive::new("data/comment.rar")
+            .open_for_listing()
+            .unwrap();
+        // "abcdef12345\n" needs 13 wide characters including the nul
+        assert_eq!(archive.read_comment(4, 16).unwrap(), b"abcdef12345\n");
+        let err = archive.read_comment(4, 8).unwrap_err();
+        assert_eq!(err.code, super::Code::SmallBuf);
+    }
+
+    #[test]
+    fn restore_unmapped_bytes() {
+        use super::wide_to_bytes;
+        let wide = ['a', 'é', '\u{FFFE}', '\u{E0E9}', 'b'].map(|c| c as _);
+        assert_eq!(wide_to_bytes(&wide), b"a\xC3\xA9\xE9b");
+    }
 }
@@ -1,11 +1,89 @@
+use std::borrow::Cow;
 use std::path::{Path, PathBuf};
 use widestring::{WideCString, WideCStr};
+use crate::error::NulError;
 
 pub(crate) type RarString = WideCString;
 pub(crate) type RarStr = WideCStr;
 
-pub(crate) fn construct(path: &Path) -> RarString {
-    WideCString::from_os_str(path).expect("Unexpected nul in path")
+pub(crate) fn construct(path: &Path) -> Result<RarString, NulError> {
+    Ok(WideCString::from_os_str(path)?)
+}
+
+/// like [`construct`], but for paths extracted to. On Windows, absolute paths get the `\\?\`
+/// prefix so that extracting does not fail once the path exceeds `MAX_PATH`.
+pub(crate) fn construct_dest(path: &Path) -> Result<RarString, NulError> {
+    construct(&long_path(path))
+}
+
+#[cfg(windows)]
+fn long_path(path: &Path) -> Cow<'_, Path> {
+    use std::ffi::OsString;
+    use std::path::{Component, Prefix};
+
+    let mut long = match path.components().next() {
+        Some(Component::Prefix(prefix)) if path.has_root() => match prefix.kind() {
+            Prefix::Disk(_) => OsString::from(r"\\?\"),
+            Prefix::UNC(..) => OsString::from(r"\\?\UNC"),
+            // verbatim and device paths are passed on unchanged
+            _ => return Cow::Borrowed(path),
+        },
+        // relative paths can exceed `MAX_PATH` just as well once resolved
+        _ => match std::path::absolute(path) {
+            Ok(absolute) if absolute.is_absolute() => {
+                return Cow::Owned(long_path(&absolute).into_owned())
+            }
+            _ => return Cow::Borrowed(path),
+        },
+    };
+    // verbatim paths are not normalized by Windows, so `.`, `..` and `/` are resolved here
+    let mut parts: Vec<&std::ffi::OsStr> = Vec::new();
+    let mut root = 0;
+    for component in path.components() {
+        match component {
+            Component::Prefix(prefix) => match prefix.kind() {
+                Prefix::UNC(server, share) => {
+                    parts.extend([server, share]);
+                    root = parts.len();
+                }
+                _ => long.push(prefix.as_os_str()),
+            },
+            Component::RootDir | Component::CurDir => {}
+            Component::ParentDir if parts.len() > root => {
+                parts.pop();
+            }
+            Component::ParentDir => {}
+            Component::Normal(part) => parts.push(part),
+        }
+    }
+    for part in parts {
+        long.push(r"\");
+        long.push(part);
+    }
+    Cow::Owned(PathBuf::from(long))
+}
+
+#[cfg(not(windows))]
+fn long_path(path: &Path) -> Cow<'_, Path> {
+    Cow::Borrowed(path)
+}
+
+#[cfg(all(test, windows))]
+mod tests {
+    use super::long_path;
+    use std::path::Path;
+
+    #[test]
+    fn long_paths() {
+        let long = |path| long_path(Path::new(path)).into_owned();
+        assert_eq!(long(r"C:\dir\.\sub\..\file"), Path::new(r"\\?\C:\dir\file"));
+        assert_eq!(long("C:/dir/file"), Path::new(r"\\?\C:\dir\file"));
+        assert_eq!(long(r"\\server\share\..\dir"), Path::new(r"\\?\UNC\server\share\dir"));
+        assert_eq!(long(r"\\?\C:\dir\..\file"), Path::new(r"\\?\C:\dir\..\file"));
+        assert!(long("relative").starts_with(r"\\?\"));
+        let name = "\u{1F600}\u{4E2D}";
+        assert_eq!(long(format!(r"C:\dir\{name}").as_str()).file_name().unwrap(), name);
+    }
 }
 
 pub(crate) fn process_file(
@@ -27,6 +105,6 @@ pub(crate) fn process_file(
 pub(crate) fn preprocess_extract(
     base: Option<&Path>,
     _filename: &PathBuf,
-) -> (Option<RarString>, Option<RarString>) {
-    (base.map(construct), None)
+) -> Result<(Option<RarString>, Option<RarString>), NulError> {
+    Ok((base.map(construct_dest).transpose()?, None))
 }
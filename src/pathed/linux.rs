@@ -1,11 +1,16 @@
 use std::ffi::{CString, CStr};
 use std::path::{Path, PathBuf};
+use crate::error::NulError;
 
 pub(crate) type RarString = CString;
 pub(crate) type RarStr = CStr;
 
-pub(crate) fn construct<P: AsRef<std::path::Path>>(path: P) -> RarString {
-    CString::new(path.as_ref().as_os_str().as_encoded_bytes()).unwrap()
+pub(crate) fn construct<P: AsRef<std::path::Path>>(path: P) -> Result<RarString, NulError> {
+    Ok(CString::new(path.as_ref().as_os_str().as_encoded_bytes())?)
+}
+
+pub(crate) fn construct_dest<P: AsRef<std::path::Path>>(path: P) -> Result<RarString, NulError> {
+    construct(path)
 }
 
 pub(crate) fn process_file(
@@ -27,6 +32,9 @@ pub(crate) fn process_file(
 pub(crate) fn preprocess_extract(
     base: Option<&Path>,
     filename: &PathBuf,
-) -> (Option<RarString>, Option<RarString>) {
-    (None, Some(construct(base.unwrap_or(".".as_ref()).join(filename))))
+) -> Result<(Option<RarString>, Option<RarString>), NulError> {
+    Ok((
+        None,
+        Some(construct_dest(base.unwrap_or(".".as_ref()).join(filename))?),
+    ))
 }
@@ -0,0 +1,167 @@
+//! Glob patterns to filter entries by their filename.
+
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Char(char),
+    /// `?`, any character but `/`
+    AnyChar,
+    /// `*`, any sequence of characters without `/`
+    AnySequence,
+    /// `**/`, any number of directories including none
+    AnyDirectories,
+    /// `**` not followed by `/`, any sequence of characters including `/`
+    AnyPath,
+    /// `[...]`, any (or with `!`, none) of the characters or ranges
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+/// A glob pattern matched against an entry's filename, with components separated by `/`.
+///
+/// Supports `?`, `*` (both not matching `/`), `**` (matching across directories)
+/// and character classes like `[a-z]` or `[!0-9]`. Malformed classes match literally.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Pattern {
+    tokens: Vec<Token>,
+}
+
+impl Pattern {
+    pub(crate) fn new(pattern: &str) -> Self {
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            match chars[i] {
+                '?' => tokens.push(Token::AnyChar),
+                '*' if chars.get(i + 1) == Some(&'*') => {
+                    i += 1;
+                    if chars.get(i + 1) == Some(&'/') {
+                        i += 1;
+                        tokens.push(Token::AnyDirectories);
+                    } else {
+                        tokens.push(Token::AnyPath);
+                    }
+                }
+                '*' => tokens.push(Token::AnySequence),
+                '[' => match parse_class(&chars[i + 1..]) {
+                    Some((token, len)) => {
+                        tokens.push(token);
+                        i += len;
+                    }
+                    None => tokens.push(Token::Char('[')),
+                },
+                c => tokens.push(Token::Char(c)),
+            }
+            i += 1;
+        }
+        Pattern { tokens }
+    }
+
+    /// whether `path` matches the whole pattern.
+    pub(crate) fn matches(&self, path: &Path) -> bool {
+        let path: Vec<String> = path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        let path: Vec<char> = path.join("/").chars().collect();
+        matches(&self.tokens, &path)
+    }
+}
+
+/// parses the class after a `[`, returning it along with the number of characters
+/// it spans including the closing `]`, or `None` if it is not closed.
+fn parse_class(chars: &[char]) -> Option<(Token, usize)> {
+    let negated = chars.first() == Some(&'!');
+    let mut i = negated as usize;
+    let mut ranges = Vec::new();
+    // a `]` right at the start is part of the class
+    while i == negated as usize || chars.get(i) != Some(&']') {
+        let start = *chars.get(i)?;
+        match (chars.get(i + 1), chars.get(i + 2)) {
+            (Some('-'), Some(&end)) if end != ']' => {
+                ranges.push((start, end));
+                i += 3;
+            }
+            _ => {
+                ranges.push((start, start));
+                i += 1;
+            }
+        }
+    }
+    Some((Token::Class { negated, ranges }, i + 1))
+}
+
+fn matches(tokens: &[Token], path: &[char]) -> bool {
+    let Some((token, rest)) = tokens.split_first() else {
+        return path.is_empty();
+    };
+    match token {
+        Token::AnySequence => (0..=path.len())
+            .take_while(|&n| n == 0 || path[n - 1] != '/')
+            .any(|n| matches(rest, &path[n..])),
+        Token::AnyPath => (0..=path.len()).any(|n| matches(rest, &path[n..])),
+        Token::AnyDirectories => {
+            matches(rest, path)
+                || (0..path.len()).any(|n| path[n] == '/' && matches(rest, &path[n + 1..]))
+        }
+        _ => match path.split_first() {
+            Some((&c, path)) if matches_char(token, c) => matches(rest, path),
+            _ => false,
+        },
+    }
+}
+
+fn matches_char(token: &Token, c: char) -> bool {
+    match token {
+        Token::Char(expected) => c == *expected,
+        Token::AnyChar => c != '/',
+        Token::Class { negated, ranges } => {
+            c != '/' && ranges.iter().any(|&(start, end)| start <= c && c <= end) != *negated
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pattern;
+    use std::path::Path;
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        Pattern::new(pattern).matches(Path::new(path))
+    }
+
+    #[test]
+    fn wildcards() {
+        assert!(matches("*.txt", "file.txt"));
+        assert!(!matches("*.txt", "dir/file.txt"));
+        assert!(!matches("*.txt", "file.txt.bak"));
+        assert!(matches("dir/?.txt", "dir/a.txt"));
+        assert!(!matches("dir?a.txt", "dir/a.txt"));
+        assert!(matches("VERSION", "VERSION"));
+    }
+
+    #[test]
+    fn recursive() {
+        assert!(matches("**/*.txt", "file.txt"));
+        assert!(matches("**/*.txt", "a/b/file.txt"));
+        assert!(matches("dir/**", "dir/a/b"));
+        assert!(!matches("dir/**", "other/a"));
+        assert!(matches("a/**/b", "a/b"));
+        assert!(matches("a/**/b", "a/x/y/b"));
+        assert!(!matches("a/**/b", "a/xb"));
+    }
+
+    #[test]
+    fn classes() {
+        assert!(matches("[a-c].txt", "b.txt"));
+        assert!(!matches("[a-c].txt", "d.txt"));
+        assert!(matches("[!a-c].txt", "d.txt"));
+        assert!(matches("[]].txt", "].txt"));
+        assert!(matches("[a-.txt", "[a-.txt"));
+    }
+}
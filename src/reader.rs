@@ -0,0 +1,148 @@
+//! Reading an entry's data through [`std::io::Read`].
+
+use crate::error::{UnrarError, UnrarResult};
+use crate::open_archive::{CursorBeforeFile, CursorBeforeHeader, FileHeader, OpenArchive, Process};
+use std::io::{self, Read, Write};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread::{self, JoinHandle};
+
+/// Number of chunks unpacked ahead of the reader before the UnRAR library is paused.
+const BUFFERED_CHUNKS: usize = 4;
+
+type Worker = JoinHandle<UnrarResult<OpenArchive<Process, CursorBeforeHeader>>>;
+
+/// Reads an entry's data as it is unpacked, see [`OpenArchive::entry_reader`].
+///
+/// The UnRAR library pushes the data to a callback instead, so the entry is unpacked
+/// on a separate thread, which waits whenever it is a few chunks ahead of the reader.
+/// Dropping the reader before the end aborts unpacking.
+#[derive(Debug)]
+pub struct EntryReader {
+    entry: FileHeader,
+    chunks: Receiver<Vec<u8>>,
+    chunk: Vec<u8>,
+    pos: usize,
+    /// the number of bytes read so far
+    done: u64,
+    worker: Option<Worker>,
+    result: Option<UnrarResult<OpenArchive<Process, CursorBeforeHeader>>>,
+}
+
+impl EntryReader {
+    pub(crate) fn new(archive: OpenArchive<Process, CursorBeforeFile>) -> Self {
+        let entry = archive.entry().clone();
+        let (sender, chunks) = mpsc::sync_channel(BUFFERED_CHUNKS);
+        let worker = thread::spawn(move || {
+            let (_, rest) = archive.extract_to_writer(ChunkWriter(sender))?;
+            Ok(rest)
+        });
+        EntryReader {
+            entry,
+            chunks,
+            chunk: Vec::new(),
+            pos: 0,
+            done: 0,
+            worker: Some(worker),
+            result: None,
+        }
+    }
+
+    /// the entry being read.
+    pub fn entry(&self) -> &FileHeader {
+        &self.entry
+    }
+
+    /// the total number of bytes the entry unpacks to, i.e. its
+    /// [`unpacked_size`](FileHeader::unpacked_size).
+    ///
+    /// For entries [split](FileHeader::is_split) across volumes, this is the size of the
+    /// whole file, as the reader continues into the next volumes.
+    pub fn current_entry_unpacked_size(&self) -> u64 {
+        self.entry.unpacked_size
+    }
+
+    /// the number of bytes read from the entry so far, which together with
+    /// [`current_entry_unpacked_size`](Self::current_entry_unpacked_size) tells the progress
+    /// of reading the entry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use unrar::Archive;
+    /// use std::io::Read;
+    ///
+    /// let archive = Archive::new("data/version.rar").open_for_processing().unwrap();
+    /// let mut reader = archive.read_header().unwrap().unwrap().entry_reader();
+    /// let mut start = [0; 5];
+    /// reader.read_exact(&mut start).unwrap();
+    /// assert_eq!(reader.current_entry_bytes_done(), 5);
+    /// assert_eq!(reader.current_entry_unpacked_size(), 11);
+    /// ```
+    pub fn current_entry_bytes_done(&self) -> u64 {
+        self.done
+    }
+
+    /// Skips the rest of the entry's data and returns the archive to process further entries,
+    /// or the error unpacking the entry failed with.
+    pub fn finish(mut self) -> UnrarResult<OpenArchive<Process, CursorBeforeHeader>> {
+        while self.chunks.recv().is_ok() {}
+        let _ = self.join();
+        self.result.take().unwrap()
+    }
+
+    /// waits for unpacking to end, returning the error it failed with.
+    fn join(&mut self) -> io::Result<()> {
+        if let Some(worker) = self.worker.take() {
+            let result = worker
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            self.result = Some(result);
+        }
+        match &self.result {
+            Some(Err(e)) => {
+                let mut err = UnrarError::from(e.code, e.when);
+                err.file = e.file.clone();
+                Err(err.into())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Read for EntryReader {
+    /// reads the next unpacked data, failing with the error unpacking failed with
+    /// once all data unpacked before has been read.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.chunk.len() {
+            match self.chunks.recv() {
+                Ok(chunk) => {
+                    self.chunk = chunk;
+                    self.pos = 0;
+                }
+                // the sender is only dropped once unpacking ended
+                Err(_) => return self.join().map(|()| 0),
+            }
+        }
+        let n = buf.len().min(self.chunk.len() - self.pos);
+        buf[..n].copy_from_slice(&self.chunk[self.pos..self.pos + n]);
+        self.pos += n;
+        self.done += n as u64;
+        Ok(n)
+    }
+}
+
+/// sends the unpacked data to the [`EntryReader`].
+struct ChunkWriter(SyncSender<Vec<u8>>);
+
+impl Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .send(buf.to_vec())
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "entry reader dropped"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use unrar::Archive;
 use unrar::VolumeInfo;
 
@@ -57,3 +58,72 @@ fn solid() {
     let archive = Archive::new("data/version.rar").open_for_listing().unwrap();
     assert!(!archive.is_solid());
 }
+
+#[test]
+fn is_volume() {
+    let archive = Archive::new("data/version.rar").open_for_listing().unwrap();
+    assert!(!archive.is_volume());
+    assert_eq!(archive.volume_count(), None);
+
+    let archive = Archive::new("data/volumes.part1.rar").open_for_listing().unwrap();
+    assert!(archive.is_volume());
+    assert_eq!(archive.volume_count(), Some(3));
+
+    // the first volume is missing
+    let archive = Archive::new("data/100M.part00002.rar").open_for_listing().unwrap();
+    assert!(archive.is_volume());
+    assert_eq!(archive.volume_count(), None);
+}
+
+#[test]
+fn first_volume() {
+    let archive = Archive::new("data/version.rar").open_for_listing().unwrap();
+    assert!(!archive.is_first_volume());
+    assert_eq!(Archive::first_volume_path("data/version.rar"), None);
+
+    let archive = Archive::new("data/oldnaming.rar").open_for_listing().unwrap();
+    assert!(archive.is_first_volume());
+    assert_eq!(
+        Archive::first_volume_path("data/oldnaming.r01"),
+        Some(PathBuf::from("data/oldnaming.rar"))
+    );
+
+    // the first volume is missing, but its name is known
+    let archive = Archive::new("data/100M.part00002.rar").open_for_listing().unwrap();
+    assert!(!archive.is_first_volume());
+    assert_eq!(
+        Archive::first_volume_path("data/100M.part00002.rar"),
+        Some(PathBuf::from("data/100M.part00001.rar"))
+    );
+}
+
+#[test]
+fn format() {
+    use unrar::RarFormat;
+    let archive = Archive::new("data/oldnaming.rar").open_for_listing().unwrap();
+    assert_eq!(archive.format(), RarFormat::Rar15);
+    let archive = Archive::new("data/volumes.part1.rar").open_for_listing().unwrap();
+    assert_eq!(archive.format(), RarFormat::Rar50);
+}
+
+#[test]
+fn authenticity() {
+    // reported by no library version yet, but never failing, not even without the password
+    for path in ["data/oldnaming.rar", "data/version.rar", "data/comment-hpw-password.rar"] {
+        let archive = Archive::new(path).open_for_listing().unwrap();
+        assert_eq!(archive.authenticity(), None);
+    }
+}
+
+#[test]
+fn quick_open() {
+    let archive = Archive::new("data/quickopen.rar").open_for_listing().unwrap();
+    assert!(archive.has_quick_open());
+    let names: Vec<_> = archive.map(|entry| entry.unwrap().filename).collect();
+    assert_eq!(names, ["a.txt", "b.txt", "c.txt"].map(PathBuf::from));
+
+    let archive = Archive::new("data/directory.rar").open_for_listing().unwrap();
+    assert!(!archive.has_quick_open());
+    let archive = Archive::new("data/oldnaming.rar").open_for_listing().unwrap();
+    assert!(!archive.has_quick_open());
+}
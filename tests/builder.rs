@@ -0,0 +1,48 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use unrar::error::Code;
+use unrar::Archive;
+
+#[test]
+fn list() {
+    let entries: Vec<_> = Archive::builder("data/version.rar")
+        .list()
+        .unwrap()
+        .map(|entry| entry.unwrap().filename)
+        .collect();
+    assert_eq!(entries, [PathBuf::from("VERSION")]);
+}
+
+#[test]
+fn process_with_password() {
+    let unpacked = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&unpacked);
+    let archive = Archive::builder("data/crypted.rar")
+        .password("unrar")
+        .on_progress(move |_, bytes| {
+            counter.fetch_add(bytes, Ordering::Relaxed);
+        })
+        .process()
+        .unwrap();
+    let (data, _) = archive.read_header().unwrap().unwrap().read().unwrap();
+    assert_eq!(data, b"target\nCargo.lock\n");
+    assert_eq!(unpacked.load(Ordering::Relaxed), data.len());
+}
+
+#[test]
+fn password_callback() {
+    let archive = Archive::builder("data/crypted.rar")
+        .password_callback(|_| None)
+        .process()
+        .unwrap();
+    let err = archive.read_header().unwrap().unwrap().read().unwrap_err();
+    assert_eq!(err.code, Code::MissingPassword);
+}
+
+#[test]
+fn build() {
+    let archive = Archive::builder("data/archive.part1.rar").build();
+    assert!(archive.is_multipart());
+    assert_eq!(archive.filename(), PathBuf::from("data/archive.part1.rar"));
+}
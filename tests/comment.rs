@@ -0,0 +1,90 @@
+use unrar::error::{Code, When};
+use unrar::Archive;
+
+#[test]
+fn comment_bytes() {
+    let archive = Archive::new("data/comment.rar").open_for_listing().unwrap();
+    assert_eq!(archive.comment_bytes().unwrap(), b"abcdef12345\n");
+    assert_eq!(archive.comment_string().unwrap(), "abcdef12345\n");
+}
+
+#[test]
+fn no_comment() {
+    let archive = Archive::new("data/version.rar")
+        .open_for_processing()
+        .unwrap();
+    assert_eq!(archive.comment_bytes().unwrap(), b"");
+    assert_eq!(archive.comment_lines().count(), 0);
+}
+
+#[test]
+fn comment_lines() {
+    let archive = Archive::new("data/comment-lines.rar")
+        .open_for_listing()
+        .unwrap();
+    assert_eq!(
+        archive.comment_string().unwrap(),
+        "  ** release **\r\nline two\n\r\nlast line\r\n"
+    );
+    let lines: Vec<_> = archive.comment_lines().collect();
+    assert_eq!(lines, ["  ** release **", "line two", "", "last line"]);
+    // decoded once, still there after reading a header
+    let archive = archive.read_header().unwrap().unwrap();
+    assert_eq!(archive.comment_lines().count(), 4);
+}
+
+#[test]
+fn comment_after_reading() {
+    let archive = Archive::new("data/comment.rar")
+        .open_for_processing()
+        .unwrap();
+    let archive = archive.read_header().unwrap().unwrap();
+    assert_eq!(archive.comment_string().unwrap(), "abcdef12345\n");
+}
+
+#[test]
+fn comment_encrypted_headers() {
+    let archive = Archive::with_password_callback("data/comment-hpw-password.rar", |attempt| {
+        (attempt == 1).then(|| b"password".to_vec())
+    })
+    .open_for_listing()
+    .unwrap();
+    assert!(archive.has_comment());
+    assert_eq!(archive.comment_string().unwrap(), "abcdef12345\n");
+}
+
+#[test]
+fn comment_encrypted_headers_wrong_password() {
+    let archive = Archive::with_password_callback("data/comment-hpw-password.rar", |attempt| {
+        (attempt == 1).then(|| b"wrong".to_vec())
+    })
+    .open_for_listing();
+    let err = archive.unwrap_err();
+    assert_eq!(err.code, Code::BadPassword);
+    assert_eq!(err.when, When::Open);
+}
+
+#[test]
+fn no_file_comment() {
+    // the archive comment is not an entry's comment
+    for entry in Archive::new("data/comment.rar").list().unwrap() {
+        assert_eq!(entry.comment(), None);
+        assert_eq!(entry.comment_bytes(), None);
+    }
+}
+
+#[test]
+fn comment_on_open() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("comment.rar");
+    std::fs::copy("data/comment.rar", &path).unwrap();
+    let archive = Archive::new(&path)
+        .with_comment_on_open(true)
+        .open_for_listing()
+        .unwrap();
+    let reopened = Archive::new(&path).open_for_listing().unwrap();
+    // the comment is kept from opening, without reopening the archive
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(archive.comment_string().unwrap(), "abcdef12345\n");
+    assert_eq!(reopened.comment_string().unwrap_err().code, Code::EOpen);
+}
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 use unrar::error::{Code, When};
-use unrar::Archive;
+use unrar::{Archive, EntryFlags};
 
 #[test]
 fn list() {
@@ -20,10 +20,11 @@ fn no_password() {
     let header = arc.read_header();
     assert!(matches!(header, Ok(Some(_))));
     let read_result = header.unwrap().unwrap().read();
-    assert!(matches!(read_result, Err(_)));
+    assert!(read_result.is_err());
     let err = read_result.unwrap_err();
     assert_eq!(err.code, Code::MissingPassword);
     assert_eq!(err.when, When::Process);
+    assert_eq!(err.file, Some(PathBuf::from(".gitignore")));
 }
 
 #[test]
@@ -77,3 +78,149 @@ fn extract_encrypted_headers() {
     let s = String::from_utf8(bytes).unwrap();
     assert_eq!(s, "target\nCargo.lock\n");
 }
+
+#[test]
+fn no_password_io_error() {
+    let err = Archive::new("data/crypted.rar")
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap()
+        .read()
+        .unwrap_err();
+    let io_err = std::io::Error::from(err);
+    assert_eq!(io_err.kind(), std::io::ErrorKind::InvalidInput);
+    let inner = io_err
+        .into_inner()
+        .unwrap()
+        .downcast::<unrar::error::UnrarError>()
+        .unwrap();
+    assert_eq!(inner.code, Code::MissingPassword);
+    assert_eq!(inner.when, When::Process);
+}
+
+#[test]
+fn password_callback_encrypted_headers() {
+    let mut entries = Archive::with_password_callback("data/comment-hpw-password.rar", |attempt| {
+        (attempt == 1).then(|| b"password".to_vec())
+    })
+    .open_for_listing()
+    .unwrap();
+    assert_eq!(
+        entries.next().unwrap().unwrap().filename,
+        PathBuf::from(".gitignore")
+    );
+}
+
+#[test]
+fn password_callback_declined() {
+    let err = Archive::with_password_callback("data/crypted.rar", |_| None)
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap()
+        .read()
+        .unwrap_err();
+    assert_eq!(err.code, Code::MissingPassword);
+    assert_eq!(err.when, When::Process);
+}
+
+#[test]
+fn encrypted_entries() {
+    let archive = Archive::new("data/crypted.rar").open_for_listing().unwrap();
+    assert!(!archive.has_encrypted_headers());
+    assert!(archive
+        .map(Result::unwrap)
+        .all(|entry| entry.is_encrypted()));
+
+    let archive = Archive::new("data/version.rar").open_for_listing().unwrap();
+    assert!(archive
+        .map(Result::unwrap)
+        .all(|entry| !entry.is_encrypted()));
+}
+
+#[test]
+fn summary() {
+    let summary = Archive::new("data/crypted.rar").summary().unwrap();
+    assert!(summary.has_encrypted_entries);
+    assert!(!summary.has_encrypted_headers);
+
+    let summary = Archive::with_password("data/comment-hpw-password.rar", "password")
+        .summary()
+        .unwrap();
+    assert!(summary.has_encrypted_entries);
+    assert!(summary.has_encrypted_headers);
+}
+
+#[test]
+fn needs_password() {
+    assert!(Archive::needs_password("data/comment-hpw-password.rar").unwrap());
+    assert!(!Archive::needs_password("data/crypted.rar").unwrap());
+    let err = Archive::needs_password("data/missing.rar").unwrap_err();
+    assert_eq!(err.code, Code::EOpen);
+}
+
+#[test]
+fn retry_password() {
+    let archive = Archive::with_password_callback("data/crypted.rar", |_| Some(b"wrong".to_vec()))
+        .open_for_processing()
+        .unwrap();
+    let mut archive = archive.read_header().unwrap().unwrap();
+    let err = archive.retry_password(b"un\0rar").unwrap_err();
+    assert_eq!(err.code, Code::ENul);
+    archive.retry_password(b"unrar").unwrap();
+    let (data, _) = archive.read().unwrap();
+    assert_eq!(data, b"target\nCargo.lock\n");
+}
+
+#[test]
+fn encryption_info() {
+    use unrar::{EncryptionInfo, EncryptionMethod};
+    let info = |path| {
+        let archive = Archive::new(path).open_for_listing().unwrap();
+        archive.encryption_info().unwrap()
+    };
+    assert_eq!(
+        info("data/crypted.rar"),
+        Some(EncryptionInfo {
+            method: EncryptionMethod::Aes128,
+            kdf_iterations: None,
+            encrypted_headers: false,
+        })
+    );
+    assert_eq!(
+        info("data/comment-hpw-password.rar"),
+        Some(EncryptionInfo {
+            method: EncryptionMethod::Aes256,
+            kdf_iterations: Some(1 << 15),
+            encrypted_headers: true,
+        })
+    );
+    // the second entry is encrypted, with a KDF count of 2^16
+    let entries = Archive::new("data/crypted-entry.rar").list().unwrap();
+    assert!(!entries[0].is_encrypted() && entries[1].is_encrypted());
+    assert_eq!(entries[1].flags(), EntryFlags::ENCRYPTED);
+    assert_eq!(
+        info("data/crypted-entry.rar"),
+        Some(EncryptionInfo {
+            method: EncryptionMethod::Aes256,
+            kdf_iterations: Some(1 << 16),
+            encrypted_headers: false,
+        })
+    );
+    assert_eq!(info("data/version.rar"), None);
+    assert_eq!(info("data/directory.rar"), None);
+}
+
+#[test]
+fn encryption_info_huge_data_size() {
+    // the file header claims u64::MAX bytes of data, far beyond the end of the archive
+    let archive = Archive::new("data/huge-data-size.rar")
+        .open_for_listing()
+        .unwrap();
+    let err = archive.encryption_info().unwrap_err();
+    assert_eq!(err.code, Code::BadArchive);
+    assert_eq!(err.when, When::Read);
+}
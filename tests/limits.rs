@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+use unrar::error::{Code, When};
+use unrar::Archive;
+
+// data/times.rar holds times.txt (6 bytes) and notimes.txt (5 bytes)
+
+#[test]
+fn max_entry_unpacked() {
+    let archive = Archive::builder("data/times.rar")
+        .max_entry_unpacked(5)
+        .process()
+        .unwrap();
+    let err = archive.read_header().unwrap().unwrap().read().unwrap_err();
+    assert_eq!(err.code, Code::SizeLimitExceeded);
+    assert_eq!(err.when, When::Process);
+    assert_eq!(err.file, Some(PathBuf::from("times.txt")));
+
+    let archive = Archive::builder("data/times.rar")
+        .max_entry_unpacked(6)
+        .process()
+        .unwrap();
+    let (data, archive) = archive.read_header().unwrap().unwrap().read().unwrap();
+    assert_eq!(data, b"times\n");
+    let (data, _) = archive.read_header().unwrap().unwrap().read().unwrap();
+    assert_eq!(data, b"none\n");
+}
+
+#[test]
+fn max_total_unpacked() {
+    let archive = Archive::builder("data/times.rar")
+        .max_total_unpacked(10)
+        .process()
+        .unwrap();
+    let (data, archive) = archive.read_header().unwrap().unwrap().read().unwrap();
+    assert_eq!(data, b"times\n");
+    let err = archive.read_header().unwrap().unwrap().read().unwrap_err();
+    assert_eq!(err.code, Code::SizeLimitExceeded);
+    assert_eq!(err.file, Some(PathBuf::from("notimes.txt")));
+}
+
+#[test]
+fn limit_while_extracting() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let header = Archive::new("data/times.rar")
+        .with_max_total_unpacked(3)
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap();
+    let err = header.extract_with_base(temp_path.path()).unwrap_err();
+    assert_eq!(err.code, Code::SizeLimitExceeded);
+}
+
+#[test]
+fn skipped_entries_not_counted() {
+    let archive = Archive::builder("data/times.rar")
+        .max_total_unpacked(5)
+        .process()
+        .unwrap();
+    let archive = archive.read_header().unwrap().unwrap().skip().unwrap();
+    let (data, _) = archive.read_header().unwrap().unwrap().read().unwrap();
+    assert_eq!(data, b"none\n");
+}
+
+#[test]
+fn suspicious_ratio() {
+    // data/bomb.rar claims to unpack 16 bytes into 1 GiB
+    let summary = Archive::new("data/bomb.rar").summary().unwrap();
+    assert_eq!(summary.unpacked_size, 1 << 30);
+    assert_eq!(summary.packed_size, 16);
+    assert!(summary.suspicious_ratio);
+    let summary = Archive::builder("data/bomb.rar")
+        .suspicious_ratio(1 << 26)
+        .build()
+        .summary()
+        .unwrap();
+    assert!(!summary.suspicious_ratio);
+
+    let summary = Archive::new("data/times.rar").summary().unwrap();
+    assert!(!summary.suspicious_ratio);
+}
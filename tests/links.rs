@@ -0,0 +1,143 @@
+use std::path::Path;
+use unrar::error::Code;
+use unrar::{Archive, OverwritePolicy, RedirectType};
+
+#[test]
+fn symlink_target() {
+    let entries = Archive::new("data/symlink.rar").list().unwrap();
+    assert_eq!(entries[0].redirect_type(), None);
+    assert_eq!(entries[0].redirect_target(), None);
+    assert_eq!(entries[1].redirect_type(), Some(RedirectType::UnixSymlink));
+    assert_eq!(entries[1].redirect_target(), Some(Path::new("target.txt")));
+}
+
+#[test]
+fn list_symlinks() {
+    // the buffer link targets are read into is reused from one header to the next
+    let entries = Archive::new("data/symlinks.rar").list().unwrap();
+    let targets: Vec<_> = entries
+        .iter()
+        .map(|entry| entry.redirect_target())
+        .collect();
+    assert_eq!(
+        targets,
+        [
+            Some(Path::new("some/long/path/to/target.txt")),
+            Some(Path::new("t.txt")),
+            None,
+        ]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn extract_symlink() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let mut archive = Archive::new("data/symlink.rar")
+        .open_for_processing()
+        .unwrap();
+    while let Some(header) = archive.read_header().unwrap() {
+        archive = header.extract_with_base(temp_path.path()).unwrap();
+    }
+    let link = temp_path.path().join("link");
+    assert!(link.symlink_metadata().unwrap().file_type().is_symlink());
+    assert_eq!(std::fs::read_link(&link).unwrap(), Path::new("target.txt"));
+    assert_eq!(std::fs::read(link).unwrap(), b"target\n");
+}
+
+#[test]
+fn reference() {
+    let entries = Archive::new("data/reference.rar").list().unwrap();
+    assert!(!entries[0].is_reference());
+    assert!(entries[2].is_reference());
+    assert_eq!(
+        entries[2].redirect_type(),
+        Some(RedirectType::FileReference)
+    );
+    assert_eq!(entries[2].redirect_target(), Some(Path::new("orig.txt")));
+}
+
+#[test]
+fn reference_without_source() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let mut archive = Archive::new("data/reference.rar")
+        .open_for_processing()
+        .unwrap();
+    let err = loop {
+        let header = archive.read_header().unwrap().unwrap();
+        match header.extract_with_base(temp_path.path()) {
+            Ok(rest) => archive = rest,
+            Err(err) => break err,
+        }
+    };
+    assert_eq!(err.code, Code::EReference);
+    assert_eq!(err.file.as_deref(), Some(Path::new("copy.txt")));
+}
+
+#[test]
+fn resolve_references() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    Archive::new("data/reference.rar")
+        .extract_all_resolving_references(temp_path.path())
+        .unwrap();
+    let copy = std::fs::read(temp_path.path().join("copy.txt")).unwrap();
+    assert_eq!(copy, b"referenced data\n");
+}
+
+#[test]
+fn resolve_renamed_references() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    std::fs::write(temp_path.path().join("orig.txt"), b"existing\n").unwrap();
+    Archive::new("data/reference.rar")
+        .with_overwrite(OverwritePolicy::Rename)
+        .extract_all_resolving_references(temp_path.path())
+        .unwrap();
+    let read = |name| std::fs::read(temp_path.path().join(name)).unwrap();
+    assert_eq!(read("orig.txt"), b"existing\n");
+    assert_eq!(read("orig(1).txt"), b"referenced data\n");
+    assert_eq!(read("copy.txt"), b"referenced data\n");
+}
+
+#[test]
+fn hardlink() {
+    let mut archive = Archive::new("data/hardlink.rar")
+        .open_for_listing()
+        .unwrap();
+    let link = archive.find(|e| e.as_ref().unwrap().filename == Path::new("link.txt"));
+    let link = link.unwrap().unwrap();
+    assert_eq!(link.redirect_type(), Some(RedirectType::Hardlink));
+    assert_eq!(link.redirect_target(), Some(Path::new("orig.txt")));
+}
+
+#[test]
+fn recreate_links() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    Archive::new("data/hardlink.rar")
+        .with_recreate_links(true)
+        .extract_all_resolving_references(temp_path.path())
+        .unwrap();
+    let orig = temp_path.path().join("orig.txt");
+    let link = temp_path.path().join("link.txt");
+    assert_eq!(std::fs::read(&link).unwrap(), b"linked data\n");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let (orig, link) = (orig.metadata().unwrap(), link.metadata().unwrap());
+        assert_eq!(orig.ino(), link.ino());
+        assert_eq!(link.nlink(), 2);
+    }
+    #[cfg(not(unix))]
+    assert!(orig.exists());
+}
+
+#[test]
+fn recreate_links_replaces_existing() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    std::fs::write(temp_path.path().join("link.txt"), b"existing\n").unwrap();
+    Archive::new("data/hardlink.rar")
+        .with_recreate_links(true)
+        .extract_all_resolving_references(temp_path.path())
+        .unwrap();
+    let link = std::fs::read(temp_path.path().join("link.txt")).unwrap();
+    assert_eq!(link, b"linked data\n");
+}
@@ -0,0 +1,82 @@
+#![cfg(target_os = "linux")]
+
+use std::path::PathBuf;
+use unrar::error::{Code, When};
+use unrar::Archive;
+
+#[test]
+fn list_from_bytes() {
+    let data = std::fs::read("data/unicode.rar").unwrap();
+    let mut entries = Archive::from_bytes(data).open_for_listing().unwrap();
+    assert_eq!(
+        entries.next().unwrap().unwrap().filename,
+        PathBuf::from("te…―st✌")
+    );
+    assert!(entries.next().is_none());
+}
+
+#[test]
+fn read_from_bytes() {
+    let data = std::fs::read("data/version.rar").unwrap();
+    let archive = Archive::from_bytes(data).open_for_processing().unwrap();
+    let (data, _) = archive.read_header().unwrap().unwrap().read().unwrap();
+    assert_eq!(data, b"unrar-0.4.0");
+}
+
+#[test]
+fn volume_from_bytes() {
+    let data = std::fs::read("data/archive.part1.rar").unwrap();
+    let err = Archive::from_bytes(data).open_for_listing().unwrap_err();
+    assert_eq!(err.code, Code::EOpen);
+    assert_eq!(err.when, When::Open);
+}
+
+#[test]
+fn garbage_from_bytes() {
+    let err = Archive::from_bytes(b"not a rar archive".to_vec())
+        .open_for_listing()
+        .unwrap_err();
+    assert_eq!(err.code, Code::BadArchive);
+    assert_eq!(err.when, When::Open);
+}
+
+#[test]
+fn comment_from_bytes() {
+    let data = std::fs::read("data/comment.rar").unwrap();
+    let archive = Archive::from_bytes(data).open_for_listing().unwrap();
+    assert_eq!(archive.comment_string().unwrap(), "abcdef12345\n");
+}
+
+#[test]
+fn read_from_fd() {
+    use std::io::Write;
+    use std::os::fd::AsRawFd;
+
+    // unlinked, so it can only be opened through the descriptor
+    let mut file = tempfile::tempfile().unwrap();
+    file.write_all(&std::fs::read("data/version.rar").unwrap())
+        .unwrap();
+    let archive = Archive::from_fd(file.as_raw_fd())
+        .open_for_processing()
+        .unwrap();
+    let (data, _) = archive.read_header().unwrap().unwrap().read().unwrap();
+    assert_eq!(data, b"unrar-0.4.0");
+}
+
+#[test]
+fn into_bytes() {
+    let data = std::fs::read("data/version.rar").unwrap();
+    let buffer = data.as_ptr();
+    let archive = Archive::from_bytes(data).open_for_processing().unwrap();
+    let archive = archive.read_header().unwrap().unwrap().skip().unwrap();
+    // the very same allocation is handed back
+    let data = archive.into_bytes().unwrap();
+    assert_eq!(data.as_ptr(), buffer);
+    assert_eq!(data, std::fs::read("data/version.rar").unwrap());
+}
+
+#[test]
+fn into_bytes_from_file() {
+    let archive = Archive::new("data/version.rar").open_for_listing().unwrap();
+    assert_eq!(archive.into_bytes(), None);
+}
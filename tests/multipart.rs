@@ -1,5 +1,7 @@
-use std::path::PathBuf;
-use unrar::Archive;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use unrar::error::{Code, When};
+use unrar::{Archive, EntryFlags, VolumeNaming};
 
 #[test]
 fn list_missing_volume() {
@@ -18,5 +20,257 @@ fn list_missing_volume() {
         assert_eq!(actual.unwrap().filename, PathBuf::from(expected));
     }
     let data = archive.next().unwrap().unwrap_err();
-    assert_eq!(format!("{data}"), "Could not open next volume");
+    assert_eq!(
+        format!("{data}"),
+        "Could not open next volume (while processing vendor/unrar/archive.cpp)"
+    );
+    assert_eq!(data.file, Some(PathBuf::from("vendor/unrar/archive.cpp")));
+}
+
+#[test]
+fn next_volume_callback() {
+    let requested = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&requested);
+    let archive = Archive::new("data/archive.part1.rar").on_next_volume(move |expected| {
+        log.lock().unwrap().push(expected.to_path_buf());
+        // relocate once, then give up as the volume is nowhere to be found
+        (log.lock().unwrap().len() == 1).then(|| PathBuf::from("data/relocated/archive.part2.rar"))
+    });
+    let err = archive
+        .open_for_listing()
+        .unwrap()
+        .find_map(Result::err)
+        .unwrap();
+    assert_eq!(err.code, Code::EOpen);
+    assert_eq!(err.when, When::Process);
+    assert_eq!(
+        *requested.lock().unwrap(),
+        [
+            PathBuf::from("data/archive.part2.rar"),
+            PathBuf::from("data/relocated/archive.part2.rar")
+        ]
+    );
+}
+
+#[test]
+fn split_flags() {
+    // RAR4, the last entry continues in the next volume
+    let mut archive = Archive::new("data/archive.part1.rar")
+        .open_for_listing_split()
+        .unwrap();
+    let mut flags = Vec::new();
+    while let Some(header) = archive.read_header().unwrap() {
+        let entry = header.entry();
+        flags.push((entry.is_split_before(), entry.is_split_after()));
+        if entry.is_split_after() {
+            break;
+        }
+        archive = header.skip().unwrap();
+    }
+    assert_eq!(flags.pop(), Some((false, true)));
+    assert!(flags.iter().all(|&flags| flags == (false, false)));
+
+    // RAR5, a volume in the middle
+    let archive = Archive::new("data/100M.part00002.rar")
+        .open_for_listing_split()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap();
+    let entry = archive.entry();
+    assert!(entry.is_split_before());
+    assert!(entry.is_split_after());
+    assert!(entry.is_split());
+    assert!(entry
+        .flags()
+        .contains(EntryFlags::SPLIT_BEFORE | EntryFlags::SPLIT_AFTER));
+}
+
+#[test]
+fn process_volumes() {
+    let mut archive = Archive::new("data/volumes.part1.rar")
+        .open_for_processing()
+        .unwrap();
+    let mut entries = Vec::new();
+    while let Some(header) = archive.read_header().unwrap() {
+        let (entry, data, rest) = header.read_bytes().unwrap();
+        entries.push((entry.filename, data));
+        archive = rest;
+    }
+    assert_eq!(
+        entries,
+        [
+            (PathBuf::from("a.txt"), b"a\n".to_vec()),
+            (PathBuf::from("b.txt"), b"bbbbbbbbbb\n".to_vec()),
+            (PathBuf::from("c.txt"), b"c\n".to_vec()),
+        ]
+    );
+}
+
+#[test]
+fn list_all() {
+    let entries = Archive::new("data/volumes.part1.rar").list().unwrap();
+    let names: Vec<_> = entries.into_iter().map(|e| e.filename).collect();
+    assert_eq!(names, ["a.txt", "b.txt", "c.txt"].map(PathBuf::from));
+
+    let err = Archive::new("data/archive.part1.rar").list().unwrap_err();
+    assert_eq!(err.code, Code::EOpen);
+}
+
+#[test]
+fn list_split_grouped() {
+    let volumes = Archive::new("data/volumes.part1.rar").list_split().unwrap();
+    assert_eq!(volumes.len(), 3);
+    assert!(volumes[1][0].is_split_before() && volumes[1][0].is_split_after());
+    assert_eq!(volumes[2][1].filename, PathBuf::from("c.txt"));
+}
+
+#[test]
+fn packed_size_of_segments() {
+    let volumes = Archive::new("data/volumes.part1.rar").list_split().unwrap();
+    let segments: Vec<_> = volumes
+        .iter()
+        .flatten()
+        .filter(|entry| entry.filename == Path::new("b.txt"))
+        .map(|entry| (entry.unpacked_size, entry.packed_size()))
+        .collect();
+    assert_eq!(segments, [(11, 4), (11, 4), (11, 3)]);
+
+    let entries = Archive::new("data/volumes.part1.rar").list().unwrap();
+    assert_eq!(entries[1].packed_size(), 4);
+    assert!((entries[1].compression_ratio() - 4.0 / 11.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn volume_paths() {
+    let volumes = Archive::volume_paths("data/volumes.part1.rar").unwrap();
+    let expected =
+        ["part1", "part2", "part3"].map(|n| PathBuf::from(format!("data/volumes.{n}.rar")));
+    assert_eq!(volumes.paths, expected);
+    assert!(volumes.complete);
+
+    let volumes = Archive::volume_paths("data/version.rar").unwrap();
+    assert_eq!(volumes.paths, [PathBuf::from("data/version.rar")]);
+    assert!(volumes.complete);
+
+    // the second volume is missing
+    let volumes = Archive::volume_paths("data/archive.part1.rar").unwrap();
+    assert_eq!(volumes.paths, [PathBuf::from("data/archive.part1.rar")]);
+    assert!(!volumes.complete);
+
+    let missing = Archive::volume_paths("data/missing.part1.rar").unwrap_err();
+    assert_eq!(missing.code, Code::EOpen);
+}
+
+#[test]
+fn total_volume_bytes() {
+    let size = |path| std::fs::metadata(path).unwrap().len();
+    let expected = ["part1", "part2", "part3"]
+        .map(|n| size(format!("data/volumes.{n}.rar")))
+        .iter()
+        .sum::<u64>();
+    let total = Archive::total_volume_bytes("data/volumes.part1.rar").unwrap();
+    assert_eq!(total, expected);
+    // the volumes found so far
+    let total = Archive::total_volume_bytes("data/archive.part1.rar").unwrap();
+    assert_eq!(total, size("data/archive.part1.rar".to_owned()));
+    let err = Archive::total_volume_bytes("data/missing.part1.rar").unwrap_err();
+    assert_eq!(err.code, Code::EOpen);
+}
+
+#[test]
+fn volume_paths_partial() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    for part in ["part1", "part2"] {
+        let name = format!("volumes.{part}.rar");
+        std::fs::copy(Path::new("data").join(&name), temp_path.path().join(&name)).unwrap();
+    }
+    let volumes = Archive::volume_paths(temp_path.path().join("volumes.part1.rar")).unwrap();
+    assert_eq!(volumes.paths.len(), 2);
+    assert!(!volumes.complete);
+}
+
+#[test]
+fn old_volume_naming() {
+    let archive = Archive::new("data/oldnaming.r01")
+        .open_for_listing()
+        .unwrap();
+    assert_eq!(archive.volume_naming(), Some(VolumeNaming::Old));
+    assert_eq!(archive.volume_count(), Some(3));
+
+    let volumes = Archive::volume_paths("data/oldnaming.rar").unwrap();
+    let expected = ["rar", "r00", "r01"].map(|ext| PathBuf::from(format!("data/oldnaming.{ext}")));
+    assert_eq!(volumes.paths, expected);
+    assert!(volumes.complete);
+    assert_eq!(volumes.naming, Some(VolumeNaming::Old));
+
+    let entries = Archive::new("data/oldnaming.rar").list().unwrap();
+    assert_eq!(entries.len(), 3);
+}
+
+#[test]
+fn renamed_new_volumes() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    for (part, ext) in [("part1", "rar"), ("part2", "r00"), ("part3", "r01")] {
+        let data = Path::new("data").join(format!("volumes.{part}.rar"));
+        std::fs::copy(data, temp_path.path().join(format!("renamed.{ext}"))).unwrap();
+    }
+    let first = temp_path.path().join("renamed.rar");
+    let archive = Archive::new(&first).open_for_listing().unwrap();
+    assert_eq!(archive.volume_naming(), Some(VolumeNaming::New));
+
+    let volumes = Archive::volume_paths(&first).unwrap();
+    assert_eq!(volumes.paths.len(), 3);
+    assert!(volumes.complete);
+    assert_eq!(volumes.naming, Some(VolumeNaming::Old));
+}
+
+#[test]
+fn summary() {
+    let summary = Archive::new("data/volumes.part1.rar").summary().unwrap();
+    // b.txt is split across all three volumes but counted once
+    assert_eq!(summary.entries, 3);
+    assert_eq!(summary.directories, 0);
+    assert_eq!(summary.unpacked_size, 15);
+    assert_eq!(summary.packed_size, 15);
+    assert_eq!(summary.compression_ratio(), 1.0);
+    assert!(summary.is_volume);
+    assert!(!summary.has_encrypted_entries);
+    assert!(!summary.suspicious_ratio);
+}
+
+#[test]
+fn continue_on_error_missing_volume() {
+    let temp_path = tempfile::tempdir().unwrap();
+    let first = temp_path.path().join("volumes.part1.rar");
+    std::fs::copy("data/volumes.part1.rar", &first).unwrap();
+    std::fs::copy(
+        "data/volumes.part3.rar",
+        temp_path.path().join("volumes.part3.rar"),
+    )
+    .unwrap();
+
+    // the library closes the archive, so c.txt cannot be reached
+    let entries = Archive::new(&first)
+        .with_continue_on_error(true)
+        .extract_all(temp_path.path().join("out"))
+        .unwrap();
+    assert_eq!(entries.len(), 2);
+    assert!(entries[0].1.is_ok());
+    assert_eq!(entries[1].0.filename, Path::new("b.txt"));
+    assert_eq!(entries[1].1.as_ref().unwrap_err().code, Code::EOpen);
+}
+
+#[test]
+fn count_entries() {
+    let count = Archive::new("data/volumes.part1.rar")
+        .count_entries()
+        .unwrap();
+    assert_eq!(count, 3);
+
+    let temp_path = tempfile::tempdir().unwrap();
+    let first = temp_path.path().join("volumes.part1.rar");
+    std::fs::copy("data/volumes.part1.rar", &first).unwrap();
+    let err = Archive::new(&first).count_entries().unwrap_err();
+    assert_eq!(err.code, Code::EOpen);
 }
@@ -0,0 +1,253 @@
+use std::fs;
+use std::path::Path;
+use unrar::error::{Code, When};
+use unrar::{Archive, EntryAction, ExtractOutcome, OverwritePolicy};
+
+fn extract(policy: OverwritePolicy, base: &Path) {
+    let mut archive = Archive::new("data/windows.rar")
+        .with_overwrite(policy)
+        .open_for_processing()
+        .unwrap();
+    while let Some(header) = archive.read_header().unwrap() {
+        archive = header.extract_with_base(base).unwrap();
+    }
+}
+
+#[test]
+fn overwrite() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let existing = temp_path.path().join("readme.txt");
+    fs::write(&existing, "old").unwrap();
+    extract(OverwritePolicy::Overwrite, temp_path.path());
+    assert_eq!(fs::read(&existing).unwrap(), b"windows\r\n");
+}
+
+#[test]
+fn skip() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let existing = temp_path.path().join("readme.txt");
+    fs::write(&existing, "old").unwrap();
+    extract(OverwritePolicy::Skip, temp_path.path());
+    assert_eq!(fs::read(&existing).unwrap(), b"old");
+    assert_eq!(fs::read_dir(temp_path.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn rename() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let existing = temp_path.path().join("readme.txt");
+    fs::write(&existing, "old").unwrap();
+    extract(OverwritePolicy::Rename, temp_path.path());
+    extract(OverwritePolicy::Rename, temp_path.path());
+    assert_eq!(fs::read(&existing).unwrap(), b"old");
+    let first = temp_path.path().join("readme(1).txt");
+    assert_eq!(fs::read(first).unwrap(), b"windows\r\n");
+    let second = temp_path.path().join("readme(2).txt");
+    assert_eq!(fs::read(second).unwrap(), b"windows\r\n");
+}
+
+#[test]
+fn extract_to() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let dest = temp_path.path().join("dest.txt");
+    fs::write(&dest, "old").unwrap();
+    let header = Archive::builder("data/windows.rar")
+        .overwrite(OverwritePolicy::Skip)
+        .process()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap();
+    // the archive can still be processed further
+    assert!(header
+        .extract_to(&dest)
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .is_none());
+    assert_eq!(fs::read(&dest).unwrap(), b"old");
+}
+
+#[test]
+fn existing_directories() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    fs::create_dir(temp_path.path().join("dir")).unwrap();
+    let mut archive = Archive::new("data/directory.rar")
+        .with_overwrite(OverwritePolicy::Skip)
+        .open_for_processing()
+        .unwrap();
+    while let Some(header) = archive.read_header().unwrap() {
+        archive = header.extract_with_base(temp_path.path()).unwrap();
+    }
+    let file = temp_path.path().join("dir/file.txt");
+    assert_eq!(fs::read(file).unwrap(), b"hello\n");
+}
+
+#[test]
+fn extract_all_to_skipped() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let archive = Archive::new("data/windows.rar")
+        .open_for_processing()
+        .unwrap();
+    let entries = archive.extract_all_to(temp_path.path()).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].0.filename, Path::new("readme.txt"));
+    assert_eq!(entries[0].1, ExtractOutcome::Written);
+
+    // the existing file is not written again
+    let archive = Archive::new("data/windows.rar")
+        .with_overwrite(OverwritePolicy::Skip)
+        .open_for_processing()
+        .unwrap();
+    let entries = archive.extract_all_to(temp_path.path()).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].1, ExtractOutcome::Skipped);
+}
+
+#[test]
+fn header_hook_skip() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let archive = Archive::new("data/directory.rar")
+        .open_for_processing()
+        .unwrap();
+    let mut seen = Vec::new();
+    let entries = archive
+        .with_header_hook(temp_path.path(), |entry| {
+            seen.push(entry.filename.clone());
+            match entry.is_file() {
+                true => EntryAction::Skip,
+                false => EntryAction::Extract,
+            }
+        })
+        .unwrap();
+    assert_eq!(seen, ["dir", "dir/file.txt", "empty"].map(Path::new));
+    let outcomes: Vec<_> = entries.into_iter().map(|(_, outcome)| outcome).collect();
+    assert_eq!(
+        outcomes,
+        [
+            ExtractOutcome::Written,
+            ExtractOutcome::Skipped,
+            ExtractOutcome::Written,
+        ]
+    );
+    assert!(temp_path.path().join("dir").is_dir());
+    assert!(!temp_path.path().join("dir/file.txt").exists());
+    assert!(temp_path.path().join("empty").is_dir());
+}
+
+#[test]
+fn header_hook_abort() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let archive = Archive::new("data/directory.rar")
+        .open_for_processing()
+        .unwrap();
+    let err = archive
+        .with_header_hook(temp_path.path(), |entry| match entry.is_file() {
+            true => EntryAction::Abort,
+            false => EntryAction::Extract,
+        })
+        .unwrap_err();
+    assert_eq!(err.code, Code::Cancelled);
+    assert_eq!(err.when, When::Process);
+    assert_eq!(err.file.as_deref(), Some(Path::new("dir/file.txt")));
+    assert!(temp_path.path().join("dir").is_dir());
+    assert!(!temp_path.path().join("empty").exists());
+}
+
+#[test]
+fn extract_outcomes() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let existing = temp_path.path().join("readme.txt");
+    fs::write(&existing, "old").unwrap();
+    let outcomes = |policy| {
+        let entries = Archive::new("data/windows.rar")
+            .with_overwrite(policy)
+            .extract_all(temp_path.path())
+            .unwrap();
+        let (_, outcome) = entries.into_iter().next().unwrap();
+        outcome.unwrap()
+    };
+    assert_eq!(outcomes(OverwritePolicy::Skip), ExtractOutcome::Skipped);
+    assert_eq!(
+        outcomes(OverwritePolicy::Rename),
+        ExtractOutcome::Renamed(temp_path.path().join("readme(1).txt"))
+    );
+    assert_eq!(fs::read(&existing).unwrap(), b"old");
+    assert_eq!(
+        outcomes(OverwritePolicy::Overwrite),
+        ExtractOutcome::Written
+    );
+    assert_eq!(fs::read(&existing).unwrap(), b"windows\r\n");
+}
+
+/// `windows.rar` with the data of its entry corrupted, written into `dir`.
+fn corrupted(dir: &Path) -> std::path::PathBuf {
+    let mut bytes = fs::read("data/windows.rar").unwrap();
+    let data = bytes.windows(9).position(|w| w == b"windows\r\n").unwrap();
+    bytes[data] = b'W';
+    let path = dir.join("corrupted.rar");
+    fs::write(&path, bytes).unwrap();
+    path
+}
+
+fn names(dir: &Path) -> Vec<std::ffi::OsString> {
+    let mut names: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn extract_to_replaces_atomically() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let dest = temp_path.path().join("dest.txt");
+    fs::write(&dest, "old").unwrap();
+    let header = Archive::new("data/windows.rar")
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap();
+    header.extract_to(&dest).unwrap();
+    assert_eq!(fs::read(&dest).unwrap(), b"windows\r\n");
+    assert_eq!(names(temp_path.path()), ["dest.txt"]);
+}
+
+#[test]
+fn extract_to_failing_keeps_existing() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let archive = corrupted(temp_path.path());
+    let dest = temp_path.path().join("dest.txt");
+    fs::write(&dest, "old").unwrap();
+    let header = Archive::new(&archive)
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap();
+    let err = header.extract_to(&dest).unwrap_err();
+    assert_eq!(err.code, unrar::error::Code::BadData);
+    assert_eq!(fs::read(&dest).unwrap(), b"old");
+    // no partial file is left behind
+    assert_eq!(names(temp_path.path()), ["corrupted.rar", "dest.txt"]);
+}
+
+#[test]
+fn extract_to_failing_allow_partial() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let archive = corrupted(temp_path.path());
+    let dest = temp_path.path().join("dest.txt");
+    let header = Archive::new(&archive)
+        .with_allow_partial(true)
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap();
+    let err = header.extract_to(&dest).unwrap_err();
+    assert_eq!(err.code, unrar::error::Code::BadData);
+    assert_eq!(fs::read(&dest).unwrap(), b"Windows\r\n");
+    assert_eq!(names(temp_path.path()), ["corrupted.rar", "dest.txt"]);
+}
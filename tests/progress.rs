@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use unrar::error::{Code, When};
+use unrar::Archive;
+
+type Log = Arc<Mutex<Vec<(PathBuf, usize)>>>;
+
+fn logging_archive(file: &'static str) -> (Archive<'static>, Log) {
+    let log = Log::default();
+    let sink = Arc::clone(&log);
+    let archive = Archive::new(file).on_progress(move |entry, bytes| {
+        sink.lock().unwrap().push((entry.filename.clone(), bytes));
+    });
+    (archive, log)
+}
+
+#[test]
+fn progress_extract_to_writer() {
+    let (archive, log) = logging_archive("data/version.rar");
+    let header = archive
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap();
+    let unpacked_size = header.entry().unpacked_size as usize;
+    let (data, _) = header.extract_to_writer(Vec::new()).unwrap();
+    let log = log.lock().unwrap();
+    assert!(log
+        .iter()
+        .all(|(name, _)| name == &PathBuf::from("VERSION")));
+    assert_eq!(
+        log.iter().map(|(_, bytes)| bytes).sum::<usize>(),
+        unpacked_size
+    );
+    assert_eq!(data.len(), unpacked_size);
+}
+
+#[test]
+fn progress_extract_to_disk() {
+    let (archive, log) = logging_archive("data/version.rar");
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let header = archive
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap();
+    let unpacked_size = header.entry().unpacked_size as usize;
+    header.extract_with_base(temp_path.path()).unwrap();
+    let total = log.lock().unwrap().iter().map(|(_, bytes)| bytes).sum::<usize>();
+    assert_eq!(total, unpacked_size);
+}
+
+#[test]
+fn no_progress_while_listing() {
+    let (archive, log) = logging_archive("data/version.rar");
+    assert_eq!(archive.open_for_listing().unwrap().count(), 1);
+    assert!(log.lock().unwrap().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "progress callback panicked")]
+fn progress_panic_is_resumed() {
+    let archive =
+        Archive::new("data/version.rar").on_progress(|_, _| panic!("progress callback panicked"));
+    let _ = archive
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap()
+        .read();
+}
+
+#[test]
+fn cancel_from_progress() {
+    let token = Arc::new(AtomicBool::new(false));
+    let trigger = Arc::clone(&token);
+    let archive = Archive::new("data/archive.part1.rar")
+        .with_cancellation(Arc::clone(&token))
+        .on_progress(move |_, _| trigger.store(true, Ordering::Relaxed));
+    let mut archive = archive.open_for_processing().unwrap();
+    let mut processed = 0;
+    let err = loop {
+        let header = archive.read_header().unwrap().unwrap();
+        match header.read() {
+            Ok((_, next)) => archive = next,
+            Err(err) => break err,
+        }
+        processed += 1;
+    };
+    assert_eq!(err.code, Code::Cancelled);
+    assert_eq!(
+        err.to_string(),
+        "Operation cancelled by callback (while processing Cargo.toml)"
+    );
+    assert_eq!(err.when, When::Process);
+    // the first chunk of data triggers the token. The first entry fits into
+    // a single chunk and completes, the next chunk is aborted.
+    assert!(token.load(Ordering::Relaxed));
+    assert_eq!(processed, 1);
+}
+
+#[test]
+fn cancelled_archive_reopens() {
+    // aborting must release the archive, so it can be opened again right away
+    let token = Arc::new(AtomicBool::new(true));
+    let result = Archive::new("data/version.rar")
+        .with_cancellation(token)
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap()
+        .read();
+    assert!(result.is_err());
+    let (data, _) = Archive::new("data/version.rar")
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap()
+        .read()
+        .unwrap();
+    assert_eq!(data, b"unrar-0.4.0");
+}
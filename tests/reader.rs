@@ -0,0 +1,86 @@
+use std::io::Read;
+use std::path::Path;
+use unrar::error::{Code, UnrarError};
+use unrar::Archive;
+
+#[test]
+fn read_entries() {
+    let mut archive = Archive::new("data/directory.rar")
+        .open_for_processing()
+        .unwrap();
+    let mut files = Vec::new();
+    while let Some(header) = archive.read_header().unwrap() {
+        let mut reader = header.entry_reader();
+        let mut data = Vec::new();
+        // a small buffer needs several reads of each chunk
+        let mut buf = [0; 2];
+        loop {
+            match reader.read(&mut buf).unwrap() {
+                0 => break,
+                n => data.extend_from_slice(&buf[..n]),
+            }
+        }
+        files.push((reader.entry().filename.clone(), data));
+        archive = reader.finish().unwrap();
+    }
+    assert_eq!(files[1].0, Path::new("dir/file.txt"));
+    assert_eq!(files[1].1, b"hello\n");
+}
+
+#[test]
+fn bytes_done_split() {
+    let archive = Archive::new("data/volumes.part1.rar")
+        .open_for_processing()
+        .unwrap();
+    let archive = archive.read_header().unwrap().unwrap().skip().unwrap();
+    // b.txt continues from part1 into part2
+    let mut reader = archive.read_header().unwrap().unwrap().entry_reader();
+    assert!(reader.entry().is_split());
+    assert_eq!(reader.current_entry_bytes_done(), 0);
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data).unwrap();
+    assert_eq!(data.len(), 11);
+    assert_eq!(reader.current_entry_bytes_done(), 11);
+    assert_eq!(reader.current_entry_unpacked_size(), 11);
+}
+
+#[test]
+fn finish_early() {
+    let mut reader = Archive::new("data/volumes.part1.rar")
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap()
+        .entry_reader();
+    let mut byte = [0];
+    reader.read_exact(&mut byte).unwrap();
+    let header = reader.finish().unwrap().read_header().unwrap().unwrap();
+    assert_eq!(header.entry().filename, Path::new("b.txt"));
+
+    // dropping the reader aborts unpacking
+    drop(header.entry_reader());
+}
+
+#[test]
+fn read_corrupted() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let mut bytes = std::fs::read("data/windows.rar").unwrap();
+    let data = bytes.windows(9).position(|w| w == b"windows\r\n").unwrap();
+    bytes[data] = b'W';
+    let archive_path = temp_path.path().join("corrupted.rar");
+    std::fs::write(&archive_path, bytes).unwrap();
+
+    let mut reader = Archive::new(&archive_path)
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap()
+        .entry_reader();
+    let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    let err = err.into_inner().unwrap().downcast::<UnrarError>().unwrap();
+    assert_eq!(err.code, Code::BadData);
+    assert_eq!(reader.finish().unwrap_err().code, Code::BadData);
+}
@@ -0,0 +1,92 @@
+use std::path::{Path, PathBuf};
+use unrar::error::{Code, When};
+use unrar::{Archive, CursorBeforeHeader, OpenArchive, Process, UnrarResult};
+
+/// extracts the `n`th entry of data/traversal.rar into `base`.
+fn extract_nth(n: usize, base: &Path) -> UnrarResult<OpenArchive<Process, CursorBeforeHeader>> {
+    let mut archive = Archive::new("data/traversal.rar")
+        .open_for_processing()
+        .unwrap();
+    for _ in 0..n {
+        archive = archive.read_header().unwrap().unwrap().skip().unwrap();
+    }
+    archive
+        .read_header()
+        .unwrap()
+        .unwrap()
+        .extract_with_base(base)
+}
+
+#[test]
+fn reject_unsafe_paths() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let base = temp_path.path().join("base");
+    for (n, filename) in ["../evil.txt", "/tmp/absolute.txt"].iter().enumerate() {
+        let err = extract_nth(n, &base).unwrap_err();
+        assert_eq!(err.code, Code::UnsafePath);
+        assert_eq!(err.when, When::Process);
+        assert_eq!(err.file, Some(PathBuf::from(filename)));
+    }
+    assert!(!temp_path.path().join("evil.txt").exists());
+
+    // stays within `base`
+    extract_nth(2, &base).unwrap();
+    assert_eq!(std::fs::read(base.join("safe.txt")).unwrap(), b"safe\n");
+}
+
+#[test]
+fn unsanitized() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let base = temp_path.path().join("base");
+    let header = Archive::builder("data/traversal.rar")
+        .sanitize(false)
+        .process()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap();
+    header.extract_with_base(&base).unwrap();
+    let escaped = temp_path.path().join("evil.txt");
+    assert_eq!(std::fs::read(escaped).unwrap(), b"evil\n");
+}
+
+#[test]
+fn strip_components() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let base = temp_path.path().join("base");
+    let entries = Archive::builder("data/strip.rar")
+        .strip_components(1)
+        .build()
+        .extract_all(&base)
+        .unwrap();
+    assert_eq!(std::fs::read(base.join("README")).unwrap(), b"readme\n");
+    assert_eq!(
+        std::fs::read(base.join("src/main.rs")).unwrap(),
+        b"fn main() {}\n"
+    );
+    assert!(!base.join("project-1.2.3").exists());
+    // contained before stripping, `../escape.txt` after
+    let (entry, result) = entries.last().unwrap();
+    assert_eq!(entry.filename, PathBuf::from("project-1.2.3/../escape.txt"));
+    assert_eq!(result.as_ref().unwrap_err().code, Code::UnsafePath);
+    assert!(!temp_path.path().join("escape.txt").exists());
+}
+
+#[test]
+fn strip_short_entries() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let entries = Archive::new("data/strip.rar")
+        .with_strip_components(2)
+        .with_continue_on_error(true)
+        .extract_all(temp_path.path())
+        .unwrap();
+    assert_eq!(entries.len(), 5);
+    assert!(entries.iter().all(|(_, result)| result.is_ok()));
+    // the entries with no more than 2 components are skipped
+    let mut files: Vec<_> = std::fs::read_dir(temp_path.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    files.sort();
+    assert_eq!(files, ["escape.txt", "main.rs"]);
+}
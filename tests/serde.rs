@@ -0,0 +1,43 @@
+#![cfg(feature = "serde")]
+
+use unrar::error::{Code, When};
+use unrar::{Archive, FileHeader, HostOs};
+
+#[test]
+fn entries_round_trip() {
+    for path in ["data/version.rar", "data/symlink.rar", "data/blake2.rar"] {
+        let entries = Archive::new(path).list().unwrap();
+        let json = serde_json::to_string(&entries).unwrap();
+        let read: Vec<FileHeader> = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, entries);
+    }
+}
+
+#[test]
+fn entry_shape() {
+    let entries = Archive::new("data/version.rar").list().unwrap();
+    let json = serde_json::to_value(&entries[0]).unwrap();
+    assert_eq!(json["filename"], "VERSION");
+    assert_eq!(json["volume"], "data/version.rar");
+    assert_eq!(json["unpacked_size"], 11);
+    assert_eq!(json["flags"], "");
+    assert_eq!(json["host_os"], "Unix");
+    assert_eq!(json["hash"]["Crc32"], entries[0].file_crc);
+    assert_eq!(json["redirect_target"], serde_json::Value::Null);
+}
+
+#[test]
+fn codes() {
+    let json = serde_json::to_string(&[Code::BadArchive, Code::Other(42)]).unwrap();
+    assert_eq!(json, r#"["BadArchive",{"Other":42}]"#);
+    let codes: Vec<Code> = serde_json::from_str(&json).unwrap();
+    assert_eq!(codes, [Code::BadArchive, Code::Other(42)]);
+    assert_eq!(
+        serde_json::to_string(&When::Process).unwrap(),
+        r#""Process""#
+    );
+    assert_eq!(
+        serde_json::to_string(&HostOs::Unknown(9)).unwrap(),
+        r#"{"Unknown":9}"#
+    );
+}
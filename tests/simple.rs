@@ -37,7 +37,562 @@ fn extract_to_tempdir() {
         let temp_file_path = temp_path.join(header.entry().filename.as_path());
         archive = header.extract_to(temp_file_path.as_path()).expect("extract_to");
     }
-    let entries = std::fs::read_dir(&temp_path).expect("read tempdir").collect::<Result<Vec<_>, _>>().unwrap();
+    let entries = std::fs::read_dir(temp_path).expect("read tempdir").collect::<Result<Vec<_>, _>>().unwrap();
     assert!(entries.len() == 1);
     assert!(entries[0].file_name() == "VERSION");
 }
+
+#[test]
+fn extract_to_failing_writer() {
+    #[derive(Debug)]
+    struct Failing;
+    impl std::io::Write for Failing {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("sink closed"))
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let err = unrar::Archive::new("data/version.rar")
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap()
+        .extract_to_writer(Failing)
+        .unwrap_err();
+    assert_eq!(err.code, unrar::error::Code::EWrite);
+    assert_eq!(err.when, unrar::error::When::Process);
+    let source = std::error::Error::source(&err).unwrap();
+    assert_eq!(source.to_string(), "sink closed");
+}
+
+#[test]
+fn nul_in_path() {
+    let err = unrar::Archive::new("data/version\0.rar")
+        .open_for_listing()
+        .unwrap_err();
+    assert_eq!(err.code, unrar::error::Code::ENul);
+    assert_eq!(err.when, unrar::error::When::Open);
+    assert!(std::error::Error::source(&err).is_some());
+
+    let err = unrar::Archive::new("data/version.rar")
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap()
+        .extract_to("VERSION\0")
+        .unwrap_err();
+    assert_eq!(err.code, unrar::error::Code::ENul);
+    assert_eq!(err.when, unrar::error::When::Process);
+}
+
+#[test]
+fn missing_archive_io_error() {
+    let err = unrar::Archive::new("data/missing.rar").open_for_listing().unwrap_err();
+    assert_eq!(err.code, unrar::error::Code::EOpen);
+    let err = std::io::Error::from(err);
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[cfg(unix)]
+#[test]
+fn unreadable_archive_io_error() {
+    use std::os::unix::fs::PermissionsExt;
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let file = temp_path.path().join("version.rar");
+    std::fs::copy("data/version.rar", &file).unwrap();
+    std::fs::set_permissions(&file, std::fs::Permissions::from_mode(0o000)).unwrap();
+    if std::fs::File::open(&file).is_ok() {
+        // permissions are not enforced, e.g. for root
+        return;
+    }
+    let err = unrar::Archive::new(&file).open_for_listing().unwrap_err();
+    assert_eq!(err.code, unrar::error::Code::EOpen);
+    // rather than the `NotFound` the code maps to
+    let err = std::io::Error::from(err);
+    assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+}
+
+#[test]
+fn directory_entries() {
+    let entries = unrar::Archive::new("data/directory.rar")
+        .open_for_listing()
+        .unwrap()
+        .map(|entry| {
+            let entry = entry.unwrap();
+            (entry.filename.clone(), entry.is_directory(), entry.is_file())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        [
+            (PathBuf::from("dir"), true, false),
+            (PathBuf::from("dir/file.txt"), false, true),
+            (PathBuf::from("empty"), true, false),
+        ]
+    );
+}
+
+#[test]
+fn extract_directories() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let mut archive = unrar::Archive::new("data/directory.rar")
+        .open_for_processing()
+        .unwrap();
+    while let Some(header) = archive.read_header().unwrap() {
+        archive = header.extract_with_base(temp_path.path()).unwrap();
+    }
+    assert!(temp_path.path().join("dir").is_dir());
+    assert!(temp_path.path().join("empty").is_dir());
+    let content = std::fs::read(temp_path.path().join("dir/file.txt")).unwrap();
+    assert_eq!(content, b"hello\n");
+}
+
+#[test]
+fn host_os() {
+    let mut archive = unrar::Archive::new("data/version.rar")
+        .open_for_listing()
+        .unwrap();
+    assert_eq!(archive.next().unwrap().unwrap().host_os(), unrar::HostOs::Unix);
+
+    let mut archive = unrar::Archive::new("data/windows.rar")
+        .open_for_listing()
+        .unwrap();
+    let entry = archive.next().unwrap().unwrap();
+    assert_eq!(entry.host_os(), unrar::HostOs::Win32);
+    assert_eq!(entry.file_attr, 0x20);
+}
+
+#[test]
+fn unix_mode() {
+    let modes = unrar::Archive::new("data/permissions.rar")
+        .open_for_listing()
+        .unwrap()
+        .map(|entry| entry.unwrap().unix_mode())
+        .collect::<Vec<_>>();
+    assert_eq!(modes, [Some(0o755), Some(0o600)]);
+
+    let mut archive = unrar::Archive::new("data/windows.rar")
+        .open_for_listing()
+        .unwrap();
+    assert_eq!(archive.next().unwrap().unwrap().unix_mode(), None);
+}
+
+#[cfg(unix)]
+#[test]
+fn extract_applies_unix_mode() {
+    use std::os::unix::fs::PermissionsExt;
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let mut archive = unrar::Archive::new("data/permissions.rar")
+        .open_for_processing()
+        .unwrap();
+    while let Some(header) = archive.read_header().unwrap() {
+        archive = header.extract_with_base(temp_path.path()).unwrap();
+    }
+    let mode = |name| {
+        let metadata = std::fs::metadata(temp_path.path().join(name)).unwrap();
+        metadata.permissions().mode() & 0o7777
+    };
+    assert_eq!(mode("run.sh"), 0o755);
+    assert_eq!(mode("private.txt"), 0o600);
+}
+
+#[test]
+fn extract_preserves_mtime() {
+    let mtime = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1700000000);
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let header = unrar::Archive::new("data/windows.rar")
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap();
+    let dest = temp_path.path().join("renamed.txt");
+    header.extract_to(&dest).unwrap();
+    let modified = std::fs::metadata(&dest).unwrap().modified().unwrap();
+    assert_eq!(modified, mtime);
+
+    let mut archive = unrar::Archive::new("data/directory.rar")
+        .open_for_processing()
+        .unwrap();
+    while let Some(header) = archive.read_header().unwrap() {
+        archive = header.extract_with_base(temp_path.path()).unwrap();
+    }
+    let file = temp_path.path().join("dir/file.txt");
+    assert_eq!(std::fs::metadata(file).unwrap().modified().unwrap(), mtime);
+}
+
+#[test]
+fn read_into_reuses_buffer() {
+    let mut buf = b"left over from a longer entry".to_vec();
+    let capacity = buf.capacity();
+    let archive = unrar::Archive::new("data/version.rar")
+        .open_for_processing()
+        .unwrap();
+    let header = archive.read_header().unwrap().unwrap();
+    let archive = header.read_into(&mut buf).unwrap();
+    assert_eq!(buf, b"unrar-0.4.0");
+    assert_eq!(buf.capacity(), capacity);
+    assert!(archive.read_header().unwrap().is_none());
+}
+
+#[test]
+fn extract_all_to_restores_directories() {
+    let secs = |secs| std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs);
+    let modified = |path: &std::path::Path| std::fs::metadata(path).unwrap().modified().unwrap();
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    unrar::Archive::new("data/dirs.rar")
+        .open_for_processing()
+        .unwrap()
+        .extract_all_to(temp_path.path())
+        .unwrap();
+    let tree = temp_path.path().join("tree");
+    assert!(tree.join("empty").is_dir());
+    assert_eq!(std::fs::read_dir(tree.join("empty")).unwrap().count(), 0);
+    // extracting `file.txt` and `empty` into `tree` does not change its time
+    assert_eq!(modified(&tree), secs(1600000000));
+    assert_eq!(modified(&tree.join("empty")), secs(1650000000));
+    assert_eq!(modified(&tree.join("file.txt")), secs(1700000000));
+
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    unrar::Archive::new("data/dirs.rar")
+        .extract_all(temp_path.path())
+        .unwrap();
+    assert_eq!(modified(&temp_path.path().join("tree")), secs(1600000000));
+    assert!(temp_path.path().join("tree/empty").is_dir());
+}
+
+#[cfg(unix)]
+#[test]
+fn extract_all_to_applies_directory_modes() {
+    use std::os::unix::fs::PermissionsExt;
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    unrar::Archive::new("data/dirs.rar")
+        .open_for_processing()
+        .unwrap()
+        .extract_all_to(temp_path.path())
+        .unwrap();
+    let mode = |name| {
+        let metadata = std::fs::metadata(temp_path.path().join(name)).unwrap();
+        metadata.permissions().mode() & 0o7777
+    };
+    assert_eq!(mode("tree"), 0o700);
+    assert_eq!(mode("tree/empty"), 0o750);
+}
+
+#[test]
+fn entry_times() {
+    use std::time::{Duration, UNIX_EPOCH};
+    let mut archive = unrar::Archive::new("data/times.rar")
+        .open_for_listing()
+        .unwrap();
+    let entry = archive.next().unwrap().unwrap();
+    assert_eq!(
+        entry.modified_time(),
+        Some(UNIX_EPOCH + Duration::from_secs(1700000000))
+    );
+    assert_eq!(
+        entry.created_time(),
+        Some(UNIX_EPOCH + Duration::from_secs(1600000000))
+    );
+    assert_eq!(
+        entry.accessed_time(),
+        Some(UNIX_EPOCH + Duration::new(1650000000, 500_000_000))
+    );
+    let entry = archive.next().unwrap().unwrap();
+    assert_eq!(entry.filename, PathBuf::from("notimes.txt"));
+    assert_eq!(entry.modified_time(), None);
+    assert_eq!(entry.created_time(), None);
+    assert_eq!(entry.accessed_time(), None);
+
+    // RAR4 DOS timestamp
+    let mut archive = unrar::Archive::new("data/version.rar")
+        .open_for_listing()
+        .unwrap();
+    let entry = archive.next().unwrap().unwrap();
+    assert!(entry.modified_time().unwrap() > UNIX_EPOCH);
+    assert_eq!(entry.created_time(), None);
+}
+
+#[test]
+fn entries_matching() {
+    let names = |pattern| -> Vec<PathBuf> {
+        unrar::Archive::new("data/directory.rar")
+            .open_for_listing()
+            .unwrap()
+            .entries_matching(pattern)
+            .map(|entry| entry.unwrap().filename)
+            .collect()
+    };
+    assert_eq!(names("*.txt"), Vec::<PathBuf>::new());
+    assert_eq!(names("**/*.txt"), [PathBuf::from("dir/file.txt")]);
+    assert_eq!(names("dir/**"), [PathBuf::from("dir/file.txt")]);
+    assert_eq!(names("*"), [PathBuf::from("dir"), PathBuf::from("empty")]);
+}
+
+#[test]
+fn extract_file() {
+    let data = unrar::Archive::new("data/times.rar")
+        .extract_file("notimes.txt")
+        .unwrap();
+    assert_eq!(data, b"none\n");
+
+    let err = unrar::Archive::new("data/times.rar")
+        .extract_file("missing.txt")
+        .unwrap_err();
+    assert_eq!(err.code, unrar::error::Code::EntryNotFound);
+    assert_eq!(err.file, Some(PathBuf::from("missing.txt")));
+    assert_eq!(err.to_string(), "No such entry in the archive (while processing missing.txt)");
+}
+
+#[test]
+fn extract_many() {
+    use std::collections::HashSet;
+    let names = HashSet::from([PathBuf::from("old.txt"), PathBuf::from("newest.txt")]);
+    let (data, missing) = unrar::Archive::new("data/filter.rar")
+        .open_for_processing()
+        .unwrap()
+        .extract_many(&names)
+        .unwrap();
+    assert_eq!(data.len(), 2);
+    assert_eq!(data[&PathBuf::from("old.txt")].len(), 1);
+    assert_eq!(data[&PathBuf::from("newest.txt")].len(), 1000);
+    assert!(missing.is_empty());
+
+    // the absent name makes it read up to the end of the archive
+    let names = HashSet::from([PathBuf::from("old.txt"), PathBuf::from("missing.txt")]);
+    let (data, missing) = unrar::Archive::new("data/filter.rar")
+        .open_for_processing()
+        .unwrap()
+        .extract_many(&names)
+        .unwrap();
+    assert_eq!(data.keys().collect::<Vec<_>>(), [&PathBuf::from("old.txt")]);
+    assert_eq!(missing, HashSet::from([PathBuf::from("missing.txt")]));
+}
+
+#[test]
+fn extract_file_to() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let dest = temp_path.path().join("file.txt");
+    unrar::Archive::new("data/directory.rar")
+        .extract_file_to("dir/file.txt", &dest)
+        .unwrap();
+    assert_eq!(std::fs::read(dest).unwrap(), b"hello\n");
+}
+
+#[test]
+fn fused_iterator() {
+    let mut archive = unrar::Archive::new("data/version.rar")
+        .open_for_listing()
+        .unwrap();
+    assert_eq!(archive.size_hint(), (0, None));
+    assert!(archive.next().unwrap().is_ok());
+    assert!(archive.next().is_none());
+    assert_eq!(archive.size_hint(), (0, Some(0)));
+    archive.force_heal();
+    assert!(archive.next().is_none());
+    assert!(archive.fuse().next().is_none());
+}
+
+#[test]
+fn compression_ratio() {
+    let entries = unrar::Archive::new("data/version.rar").list().unwrap();
+    // tiny files grow when compressed
+    assert_eq!((entries[0].unpacked_size, entries[0].packed_size()), (11, 21));
+    assert!(entries[0].compression_ratio() > 1.0);
+
+    let entries = unrar::Archive::new("data/utf8.rar").list().unwrap();
+    assert_eq!(entries[0].unpacked_size, 0);
+    assert_eq!(entries[0].compression_ratio(), 0.0);
+}
+
+#[test]
+fn method_and_version() {
+    use unrar::CompressionMethod;
+    // RAR4
+    let entries = unrar::Archive::new("data/version.rar").list().unwrap();
+    assert_eq!(entries[0].compression_method(), CompressionMethod::Normal);
+    assert!(!entries[0].is_stored());
+    assert_eq!(entries[0].min_version(), 29);
+    // RAR5
+    let entries = unrar::Archive::new("data/comment.rar").list().unwrap();
+    assert_eq!(entries[0].compression_method(), CompressionMethod::Store);
+    assert!(entries[0].is_stored());
+    assert_eq!(entries[0].min_version(), 50);
+}
+
+#[test]
+fn send_to_thread() {
+    let archive = unrar::Archive::new("data/version.rar")
+        .open_for_processing()
+        .unwrap();
+    let data = std::thread::spawn(move || {
+        let header = archive.read_header().unwrap().unwrap();
+        header.read().unwrap().0
+    })
+    .join()
+    .unwrap();
+    assert_eq!(data, b"unrar-0.4.0");
+}
+
+#[test]
+fn clone_entries() {
+    let mut archive = unrar::Archive::new("data/times.rar")
+        .open_for_listing()
+        .unwrap();
+    let first = archive.next().unwrap().unwrap();
+    let kept = first.clone();
+    let second = archive.next().unwrap().unwrap();
+    assert_eq!(kept, first);
+    assert_ne!(kept, second);
+
+    let snapshot = unrar::Archive::new("data/times.rar").list().unwrap();
+    assert_eq!(snapshot, [first, second]);
+}
+
+#[test]
+fn seek_to() {
+    let archive = unrar::Archive::new("data/volumes.part1.rar")
+        .open_for_processing()
+        .unwrap();
+    let header = archive.seek_to("c.txt").unwrap();
+    assert_eq!(header.entry().filename, PathBuf::from("c.txt"));
+    let (data, archive) = header.read().unwrap();
+    assert_eq!(data, b"c\n");
+    assert!(archive.read_header().unwrap().is_none());
+
+    let archive = unrar::Archive::new("data/directory.rar")
+        .open_for_listing()
+        .unwrap();
+    let err = archive.seek_to("missing.txt").unwrap_err();
+    assert_eq!(err.code, unrar::error::Code::EntryNotFound);
+    assert_eq!(err.file, Some(PathBuf::from("missing.txt")));
+}
+
+#[test]
+fn dictionary_size() {
+    let entries = unrar::Archive::new("data/solid.rar").list().unwrap();
+    assert_eq!(entries[0].dictionary_size(), Some(1 << 20));
+    // all but the first entry of a solid archive are unpacked after the ones before
+    assert!(!entries[0].flags().contains(unrar::EntryFlags::SOLID));
+    assert!(entries[1..].iter().all(|e| e.flags().contains(unrar::EntryFlags::SOLID)));
+    let entries = unrar::Archive::new("data/directory.rar").list().unwrap();
+    assert!(entries[0].is_directory());
+    assert_eq!(entries[0].dictionary_size(), None);
+    assert_eq!(entries[1].dictionary_size(), Some(128 << 10));
+    // RAR4 archives store no dictionary size
+    let entries = unrar::Archive::new("data/version.rar").list().unwrap();
+    assert_eq!(entries[0].dictionary_size(), None);
+}
+
+#[test]
+fn entries_modified() {
+    use std::time::{Duration, UNIX_EPOCH};
+    let list = || {
+        unrar::Archive::new("data/filter.rar")
+            .open_for_listing()
+            .unwrap()
+    };
+    let names = |entries: &mut dyn Iterator<Item = unrar::UnrarResult<unrar::FileHeader>>| {
+        entries
+            .map(|entry| entry.unwrap().filename)
+            .collect::<Vec<_>>()
+    };
+    let time = UNIX_EPOCH + Duration::from_secs(1700000000);
+    assert_eq!(
+        names(&mut list().entries_modified_after(time)),
+        [PathBuf::from("newest.txt")]
+    );
+    assert_eq!(
+        names(&mut list().entries_modified_before(time)),
+        [PathBuf::from("old.txt")]
+    );
+    assert_eq!(
+        names(&mut list().entries_modified_within(time..)),
+        [PathBuf::from("new.txt"), PathBuf::from("newest.txt")]
+    );
+
+    // entries without a modification time are left out
+    let archive = unrar::Archive::new("data/times.rar")
+        .open_for_listing()
+        .unwrap();
+    assert_eq!(
+        names(&mut archive.entries_modified_within(..)),
+        [PathBuf::from("times.txt")]
+    );
+}
+
+#[test]
+fn entries_by_size() {
+    let list = || {
+        unrar::Archive::new("data/filter.rar")
+            .open_for_listing()
+            .unwrap()
+    };
+    let names: Vec<_> = list()
+        .entries_larger_than(1)
+        .map(|entry| entry.unwrap().filename)
+        .collect();
+    assert_eq!(names, [PathBuf::from("new.txt"), PathBuf::from("newest.txt")]);
+    let names: Vec<_> = list()
+        .entries_smaller_than(100)
+        .map(|entry| entry.unwrap().filename)
+        .collect();
+    assert_eq!(names, [PathBuf::from("old.txt")]);
+}
+
+#[test]
+fn precise_times() {
+    use std::time::{Duration, UNIX_EPOCH};
+    let mut archive = unrar::Archive::new("data/precise-times.rar").open_for_listing().unwrap();
+    let expected = Some(UNIX_EPOCH + Duration::new(1700000000, 123_456_700));
+    // stored as `FILETIME`
+    let entry = archive.next().unwrap().unwrap();
+    assert_eq!(entry.filename, PathBuf::from("filetime.txt"));
+    assert_eq!(entry.modified_time(), expected);
+    // stored as Unix time with 123456789 ns
+    let entry = archive.next().unwrap().unwrap();
+    assert_eq!(entry.filename, PathBuf::from("unixns.txt"));
+    assert_eq!(entry.modified_time(), expected);
+}
+
+#[test]
+fn extracting_iter() {
+    let archive = unrar::Archive::new("data/directory.rar").open_for_processing().unwrap();
+    let entries: Vec<_> = archive
+        .into_extracting_iter()
+        .map(|entry| entry.map(|(entry, data)| (entry.filename, data)))
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        entries,
+        [
+            (PathBuf::from("dir"), Vec::new()),
+            (PathBuf::from("dir/file.txt"), b"hello\n".to_vec()),
+            (PathBuf::from("empty"), Vec::new()),
+        ]
+    );
+}
+
+#[test]
+fn extracting_iter_corrupted() {
+    let temp_path = tempfile::tempdir().unwrap();
+    let mut bytes = std::fs::read("data/directory.rar").unwrap();
+    let data = bytes.windows(6).position(|w| w == b"hello\n").unwrap();
+    bytes[data] = b'H';
+    let path = temp_path.path().join("corrupted.rar");
+    std::fs::write(&path, bytes).unwrap();
+
+    let archive = unrar::Archive::new(&path).open_for_processing().unwrap();
+    let mut entries = archive.into_extracting_iter();
+    assert!(entries.next().unwrap().is_ok());
+    let err = entries.next().unwrap().unwrap_err();
+    assert_eq!(err.code, unrar::error::Code::BadData);
+    // the next entry is read all the same
+    assert_eq!(entries.next().unwrap().unwrap().0.filename, PathBuf::from("empty"));
+    assert!(entries.next().is_none());
+    assert!(entries.next().is_none());
+}
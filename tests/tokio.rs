@@ -0,0 +1,40 @@
+#![cfg(feature = "tokio")]
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use unrar::error::Code;
+use unrar::Archive;
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn extract_file_async() {
+    let data = runtime()
+        .block_on(unrar::extract_file_async("data/version.rar", "VERSION"))
+        .unwrap();
+    assert_eq!(data, b"unrar-0.4.0");
+
+    let err = runtime()
+        .block_on(unrar::extract_file_async("data/version.rar", "missing"))
+        .unwrap_err();
+    assert_eq!(err.code, Code::EntryNotFound);
+}
+
+#[test]
+fn extract_file_async_cancelled() {
+    let token = Arc::new(AtomicBool::new(false));
+    let archive = Archive::new_owned("data/version.rar").with_cancellation(Arc::clone(&token));
+    let err = runtime()
+        .block_on(async {
+            // the future does not run before it is awaited
+            let extracting = archive.extract_file_async("VERSION");
+            token.store(true, Ordering::Relaxed);
+            extracting.await
+        })
+        .unwrap_err();
+    assert_eq!(err.code, Code::Cancelled);
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "tracing")]
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+use unrar::Archive;
+
+/// records the fields of all events as `name=value` strings.
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<String>>>);
+
+struct Fields(Vec<String>);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.push(format!("{}={:?}", field.name(), value));
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &Attributes) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event) {
+        let mut fields = Fields(Vec::new());
+        event.record(&mut fields);
+        self.0.lock().unwrap().push(fields.0.join(" "));
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn ffi_calls() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let archive = Archive::new("data/version.rar")
+            .open_for_processing()
+            .unwrap();
+        let (data, archive) = archive.read_header().unwrap().unwrap().read().unwrap();
+        assert_eq!(data, b"unrar-0.4.0");
+        assert!(archive.read_header().unwrap().is_none());
+    });
+    let events = recorder.0.lock().unwrap();
+    let events: Vec<_> = events.iter().map(String::as_str).collect();
+    assert_eq!(
+        events,
+        [
+            "message=RAROpenArchiveEx archive=data/version.rar mode=1",
+            "message=RAROpenArchiveEx returned archive=data/version.rar result=Success",
+            "message=RARReadHeaderEx",
+            r#"message=RARReadHeaderEx returned result=Success entry=Some("VERSION")"#,
+            r#"message=RARProcessFile entry=Some("VERSION") operation=Test"#,
+            r#"message=RARProcessFile returned entry=Some("VERSION") operation=Test result=Success"#,
+            "message=RARReadHeaderEx",
+            "message=RARReadHeaderEx returned result=EndArchive entry=None",
+        ]
+    );
+}
@@ -0,0 +1,59 @@
+use std::path::{Path, PathBuf};
+use unrar::error::{Code, When};
+use unrar::Archive;
+
+/// copies the first `len` bytes of `archive` into a file in `dir`.
+fn cut(archive: &str, len: usize, dir: &Path) -> PathBuf {
+    let data = std::fs::read(archive).unwrap();
+    let path = dir.join("cut.rar");
+    std::fs::write(&path, &data[..len]).unwrap();
+    path
+}
+
+#[test]
+fn truncated_listing() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = cut("data/directory.rar", 100, dir.path());
+    let entries: Vec<_> = Archive::new(&path).open_for_listing().unwrap().collect();
+    let (last, entries) = entries.split_last().unwrap();
+    assert!(entries.iter().all(Result::is_ok));
+    let err = last.as_ref().unwrap_err();
+    assert_eq!(err.code, Code::Truncated);
+    assert_eq!(err.when, When::Read);
+}
+
+#[test]
+fn truncated_processing() {
+    let dir = tempfile::tempdir().unwrap();
+    let len = std::fs::metadata("data/version.rar").unwrap().len() as usize;
+    let path = cut("data/version.rar", len - 10, dir.path());
+    let mut archive = Archive::new(&path).open_for_processing().unwrap();
+    let err = loop {
+        match archive.read_header() {
+            Ok(Some(header)) => archive = header.skip().unwrap(),
+            Ok(None) => panic!("truncated archive read to its end"),
+            Err(e) => break e,
+        }
+    };
+    assert_eq!(err.code, Code::Truncated);
+}
+
+#[test]
+fn missing_end_header() {
+    // the end of archive header is optional, the archive is complete without it
+    let dir = tempfile::tempdir().unwrap();
+    let len = std::fs::metadata("data/directory.rar").unwrap().len() as usize;
+    let path = cut("data/directory.rar", len - 8, dir.path());
+    let entries = Archive::new(&path).open_for_listing().unwrap();
+    assert!(entries.map(Result::unwrap).count() > 0);
+}
+
+#[test]
+fn garbage_is_not_truncated() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("garbage.rar");
+    std::fs::write(&path, b"this is not an archive").unwrap();
+    let err = Archive::new(&path).open_for_listing().unwrap_err();
+    assert_ne!(err.code, Code::Truncated);
+    assert_eq!(err.when, When::Open);
+}
@@ -66,3 +66,17 @@ fn unicode_entry_extract() {
     assert_eq!(entries.len(), 1);
     assert_eq!(&entries[0].file_name(), "unicodefilename❤️.txt");
 }
+
+#[test]
+#[cfg(unix)]
+fn legacy_codepage_name() {
+    use std::os::unix::ffi::OsStrExt;
+    let parent = tempfile::tempdir().unwrap();
+    let archive = Archive::new("data/legacy.rar").open_for_processing().unwrap();
+    let archive = archive.read_header().unwrap().unwrap();
+    let filename = archive.entry().filename.clone();
+    assert_eq!(filename.as_os_str().as_bytes(), b"caf\xe9.txt");
+    assert_eq!(archive.entry().filename_lossy(), "caf\u{FFFD}.txt");
+    archive.extract_with_base(parent.path()).expect("extraction failed");
+    assert_eq!(std::fs::read(parent.path().join(filename)).unwrap(), b"latin-1\n");
+}
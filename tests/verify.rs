@@ -0,0 +1,256 @@
+use std::path::PathBuf;
+use unrar::error::{Code, When};
+use unrar::{Archive, HashKind, StoredHash};
+
+#[test]
+fn crc32() {
+    let mut archive = Archive::new("data/version.rar").open_for_listing().unwrap();
+    assert_eq!(archive.next().unwrap().unwrap().crc32(), 0xC67D_F345);
+
+    // hashed with BLAKE2sp instead
+    let mut archive = Archive::new("data/blake2.rar").open_for_listing().unwrap();
+    assert_eq!(archive.next().unwrap().unwrap().crc32(), 0);
+
+    let mut archive = Archive::new("data/directory.rar")
+        .open_for_listing()
+        .unwrap();
+    let dir = archive.next().unwrap().unwrap();
+    assert!(dir.is_directory());
+    assert_eq!(dir.crc32(), 0);
+}
+
+#[test]
+fn stored_hash() {
+    let entry = |path| {
+        let mut archive = Archive::new(path).open_for_listing().unwrap();
+        archive.next().unwrap().unwrap()
+    };
+    let blake2 = entry("data/blake2.rar");
+    assert_eq!(blake2.hash_kind(), HashKind::Blake2sp);
+    assert_eq!(
+        blake2.stored_hash(),
+        StoredHash::Blake2sp(blake2.blake2sp().unwrap())
+    );
+    let crc = entry("data/version.rar");
+    assert_eq!(crc.hash_kind(), HashKind::Crc32);
+    assert_eq!(crc.stored_hash(), StoredHash::Crc32(0xC67D_F345));
+    let dir = entry("data/directory.rar");
+    assert_eq!(dir.hash_kind(), HashKind::None);
+    assert_eq!(dir.stored_hash(), StoredHash::None);
+    assert_eq!(dir.stored_hash().kind(), HashKind::None);
+}
+
+#[test]
+fn extract_and_verify() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let mut archive = Archive::new("data/directory.rar")
+        .open_for_processing()
+        .unwrap();
+    while let Some(header) = archive.read_header().unwrap() {
+        archive = header.extract_and_verify(temp_path.path()).unwrap();
+    }
+    let file = temp_path.path().join("dir/file.txt");
+    assert_eq!(std::fs::read(file).unwrap(), b"hello\n");
+    assert!(temp_path.path().join("empty").is_dir());
+}
+
+#[test]
+fn verify_corrupted() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let mut bytes = std::fs::read("data/windows.rar").unwrap();
+    let data = bytes.windows(9).position(|w| w == b"windows\r\n").unwrap();
+    bytes[data] = b'W';
+    let archive_path = temp_path.path().join("corrupted.rar");
+    std::fs::write(&archive_path, bytes).unwrap();
+
+    let header = Archive::new(&archive_path)
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap();
+    let err = header.extract_and_verify(temp_path.path()).unwrap_err();
+    assert_eq!(err.code, Code::BadData);
+    assert_eq!(err.when, When::Process);
+    assert_eq!(err.file, Some(PathBuf::from("readme.txt")));
+}
+
+#[test]
+fn blake2sp() {
+    let mut archive = Archive::new("data/blake2.rar").open_for_listing().unwrap();
+    let hash = archive.next().unwrap().unwrap().blake2sp().unwrap();
+    let hex: String = hash.iter().map(|b| format!("{b:02x}")).collect();
+    assert_eq!(
+        hex,
+        "6944442ec2faca16200543da885bcb0fdfc651d0eb44b1cb5ea3614a82e2cea7"
+    );
+
+    let mut archive = Archive::new("data/directory.rar")
+        .open_for_listing()
+        .unwrap();
+    assert_eq!(archive.next().unwrap().unwrap().blake2sp(), None);
+}
+
+#[cfg(feature = "blake2")]
+#[test]
+fn verify_blake2() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let header = Archive::new("data/blake2.rar")
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap();
+    header.extract_and_verify(temp_path.path()).unwrap();
+    let data = std::fs::read(temp_path.path().join("blake2.txt")).unwrap();
+    assert_eq!(data, b"hello blake2\n");
+}
+
+#[cfg(not(feature = "blake2"))]
+#[test]
+fn verify_blake2_unsupported() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let header = Archive::new("data/blake2.rar")
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap();
+    let err = header.extract_and_verify(temp_path.path()).unwrap_err();
+    assert_eq!(err.code, Code::Unverifiable);
+    assert_eq!(err.file, Some(PathBuf::from("blake2.txt")));
+    assert!(!temp_path.path().join("blake2.txt").exists());
+}
+
+#[test]
+fn blake2_checked_by_library() {
+    let (data, _) = Archive::new("data/blake2.rar")
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap()
+        .read()
+        .unwrap();
+    assert_eq!(data, b"hello blake2\n");
+}
+
+#[test]
+fn test_archive() {
+    let report = Archive::new("data/directory.rar").test().unwrap();
+    assert!(report.passed());
+    assert_eq!(report.failures().count(), 0);
+
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let mut bytes = std::fs::read("data/directory.rar").unwrap();
+    let data = bytes.windows(6).position(|w| w == b"hello\n").unwrap();
+    bytes[data] = b'H';
+    let archive_path = temp_path.path().join("corrupted.rar");
+    std::fs::write(&archive_path, bytes).unwrap();
+
+    let report = Archive::new(&archive_path).test().unwrap();
+    assert!(!report.passed());
+    // testing goes on after the corrupted entry
+    let names: Vec<_> = report.entries.iter().map(|(e, _)| &e.filename).collect();
+    assert_eq!(names, ["dir", "dir/file.txt", "empty"]);
+    let failures: Vec<_> = report.failures().collect();
+    assert_eq!(failures.len(), 1);
+    let (entry, err) = failures[0];
+    assert_eq!(entry.filename, PathBuf::from("dir/file.txt"));
+    assert_eq!(err.code, Code::BadData);
+    assert_eq!(err.when, When::Process);
+}
+
+#[test]
+fn partial_data() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let mut bytes = std::fs::read("data/windows.rar").unwrap();
+    let data = bytes.windows(9).position(|w| w == b"windows\r\n").unwrap();
+    bytes[data] = b'W';
+    let archive_path = temp_path.path().join("corrupted.rar");
+    std::fs::write(&archive_path, bytes).unwrap();
+
+    let header = Archive::new(&archive_path)
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap();
+    let err = header.read_bytes().unwrap_err();
+    assert_eq!(err.code, Code::BadData);
+    assert_eq!(err.partial_data(), None);
+
+    let header = Archive::builder(&archive_path)
+        .allow_partial(true)
+        .process()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap();
+    let err = header.read_bytes().unwrap_err();
+    assert_eq!(err.code, Code::BadData);
+    assert_eq!(err.file, Some(PathBuf::from("readme.txt")));
+    assert_eq!(err.into_partial_data().unwrap(), b"Windows\r\n");
+}
+
+#[test]
+fn read_into_corrupted() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let mut bytes = std::fs::read("data/windows.rar").unwrap();
+    let data = bytes.windows(9).position(|w| w == b"windows\r\n").unwrap();
+    bytes[data] = b'W';
+    let archive_path = temp_path.path().join("corrupted.rar");
+    std::fs::write(&archive_path, bytes).unwrap();
+
+    let header = Archive::new(&archive_path)
+        .open_for_processing()
+        .unwrap()
+        .read_header()
+        .unwrap()
+        .unwrap();
+    let mut buf = b"previous entry".to_vec();
+    let err = header.read_into(&mut buf).unwrap_err();
+    assert_eq!(err.code, Code::BadData);
+    assert_eq!(buf, b"Windows\r\n");
+}
+
+#[test]
+fn continue_on_error() {
+    let temp_path = tempfile::tempdir().expect("creating tempdir");
+    let mut bytes = std::fs::read("data/directory.rar").unwrap();
+    let data = bytes.windows(6).position(|w| w == b"hello\n").unwrap();
+    bytes[data] = b'H';
+    let archive_path = temp_path.path().join("corrupted.rar");
+    std::fs::write(&archive_path, bytes).unwrap();
+
+    let out = temp_path.path().join("stopped");
+    let entries = Archive::new(&archive_path).extract_all(&out).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].1.as_ref().unwrap_err().code, Code::BadData);
+    assert!(!out.join("empty").exists());
+
+    let out = temp_path.path().join("continued");
+    let entries = Archive::builder(&archive_path)
+        .continue_on_error(true)
+        .build()
+        .extract_all(&out)
+        .unwrap();
+    let results: Vec<_> = entries
+        .iter()
+        .map(|(e, result)| {
+            (
+                e.filename.to_str().unwrap(),
+                result.as_ref().err().map(|e| e.code),
+            )
+        })
+        .collect();
+    assert_eq!(
+        results,
+        [
+            ("dir", None),
+            ("dir/file.txt", Some(Code::BadData)),
+            ("empty", None)
+        ]
+    );
+    assert!(out.join("empty").is_dir());
+}
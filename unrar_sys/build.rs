@@ -1,4 +1,13 @@
 fn main() {
+    if std::env::var_os("CARGO_FEATURE_STATIC").is_none() {
+        // link the UnRAR library installed on the system instead of building it
+        println!("cargo:rerun-if-env-changed=UNRAR_LIB_DIR");
+        if let Some(dir) = std::env::var_os("UNRAR_LIB_DIR") {
+            println!("cargo:rustc-link-search=native={}", std::path::Path::new(&dir).display());
+        }
+        println!("cargo:rustc-link-lib=dylib=unrar");
+        return;
+    }
     if cfg!(windows) {
         println!("cargo:rustc-flags=-lpowrprof");
         println!("cargo:rustc-link-lib=shell32");
@@ -81,6 +81,13 @@ pub const RAR_HASH_NONE: c_uint = 0;
 pub const RAR_HASH_CRC32: c_uint = 1;
 pub const RAR_HASH_BLAKE2: c_uint = 2;
 
+pub const FSREDIR_NONE: c_uint = 0;
+pub const FSREDIR_UNIXSYMLINK: c_uint = 1;
+pub const FSREDIR_WINSYMLINK: c_uint = 2;
+pub const FSREDIR_JUNCTION: c_uint = 3;
+pub const FSREDIR_HARDLINK: c_uint = 4;
+pub const FSREDIR_FILECOPY: c_uint = 5;
+
 pub const RHDF_SPLITBEFORE: c_uint = 1 << 0; // 1, 0x1
 pub const RHDF_SPLITAFTER: c_uint = 1 << 1; // 2, 0x2
 pub const RHDF_ENCRYPTED: c_uint = 1 << 2; // 4, 0x4
@@ -117,7 +124,9 @@ pub struct Handle { _private: [u8; 0] }
 
 // ----------------- STRUCTS ----------------- //
 
-#[repr(C)]
+// dll.hpp declares all structs within `#pragma pack(push, 1)`
+
+#[repr(C, packed)]
 pub struct HeaderData {
     pub archive_name: [c_char; 260],
     pub filename: [c_char; 260],
@@ -136,7 +145,7 @@ pub struct HeaderData {
     pub comment_state: c_uint,
 }
 
-#[repr(C)]
+#[repr(C, packed)]
 pub struct HeaderDataEx {
     pub archive_name: [c_char; 1024],
     pub archive_name_w: [wchar_t; 1024],
@@ -173,7 +182,7 @@ pub struct HeaderDataEx {
     pub reserved: [c_uint; 988],
 }
 
-#[repr(C)]
+#[repr(C, packed)]
 pub struct OpenArchiveData {
     pub archive_name: *const c_char,
     pub open_mode: c_uint,
@@ -184,7 +193,7 @@ pub struct OpenArchiveData {
     pub comment_state: c_uint,
 }
 
-#[repr(C)]
+#[repr(C, packed)]
 pub struct OpenArchiveDataEx {
     pub archive_name: *const c_char,
     pub archive_name_w: *const wchar_t,
@@ -204,11 +213,13 @@ pub struct OpenArchiveDataEx {
 
 // ----------------- BINDINGS ----------------- //
 
-#[link(name = "unrar", kind = "static")]
-#[cfg_attr(all(windows, target_env = "gnu"), link(name = "stdc++", kind = "static", modifiers = "-bundle"))]
-#[cfg_attr(target_os = "macos", link(name = "c++"))]
-#[cfg_attr(any(target_os = "freebsd", target_os = "openbsd"), link(name = "c++"))]
-#[cfg_attr(any(target_os = "linux", target_os = "netbsd"), link(name = "stdc++"))]
+// without the `static` feature, the build script links the system's UnRAR library,
+// which already links the C++ standard library itself
+#[cfg_attr(feature = "static", link(name = "unrar", kind = "static"))]
+#[cfg_attr(all(feature = "static", windows, target_env = "gnu"), link(name = "stdc++", kind = "static", modifiers = "-bundle"))]
+#[cfg_attr(all(feature = "static", target_os = "macos"), link(name = "c++"))]
+#[cfg_attr(all(feature = "static", any(target_os = "freebsd", target_os = "openbsd")), link(name = "c++"))]
+#[cfg_attr(all(feature = "static", any(target_os = "linux", target_os = "netbsd")), link(name = "stdc++"))]
 extern "C" {
     pub fn RAROpenArchive(data: *const OpenArchiveData) -> *const Handle;
 
@@ -376,4 +387,14 @@ mod tests {
     fn test_version() {
         assert_eq!(unsafe { super::RARGetDllVersion() }, 9);
     }
+
+    #[test]
+    fn test_packed_layout() {
+        use super::*;
+        use std::mem::offset_of;
+        // pointers directly follow the `c_uint` before them, without padding
+        assert_eq!(offset_of!(HeaderData, comment_buffer), offset_of!(HeaderData, file_attr) + 4);
+        assert_eq!(offset_of!(HeaderDataEx, comment_buffer), offset_of!(HeaderDataEx, file_attr) + 4);
+        assert_eq!(offset_of!(OpenArchiveDataEx, comment_buffer_w), offset_of!(OpenArchiveDataEx, op_flags) + 4);
+    }
 }